@@ -0,0 +1,413 @@
+use std::collections::HashSet;
+
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::Token;
+
+pub(crate) enum BuilderPattern {
+    Owned,
+    Mutable,
+    Immutable,
+}
+
+pub(crate) struct StructAttributes {
+    pub(crate) default: bool,
+    pub(crate) pattern: BuilderPattern,
+    pub(crate) into: bool,
+    pub(crate) validate: Option<syn::Path>,
+    pub(crate) name: Option<syn::Ident>,
+    pub(crate) vis: Option<syn::Visibility>,
+    pub(crate) setter_prefix: Option<syn::Ident>,
+    pub(crate) typestate: bool,
+    pub(crate) serde: bool,
+    pub(crate) no_std: bool,
+    pub(crate) doc: Option<syn::LitStr>,
+    pub(crate) context: Option<syn::Type>,
+    pub(crate) to_builder: bool,
+}
+
+pub(crate) struct FieldAttributes {
+    pub(crate) each: Option<syn::Ident>,
+    pub(crate) name: Option<syn::Ident>,
+    pub(crate) default: Option<proc_macro2::TokenStream>,
+    pub(crate) default_with: Option<syn::Path>,
+    pub(crate) fallible_default: bool,
+    pub(crate) into: bool,
+    pub(crate) validate: Option<syn::Path>,
+    pub(crate) vis: Option<syn::Visibility>,
+    pub(crate) setter_prefix: Option<syn::Ident>,
+    pub(crate) setter_name: Option<syn::Ident>,
+    pub(crate) skip_setter: bool,
+    pub(crate) optional: bool,
+    pub(crate) required: bool,
+    pub(crate) strip_option: bool,
+    pub(crate) transform: Option<syn::ExprClosure>,
+    pub(crate) sub_builder: bool,
+    pub(crate) doc: Option<syn::LitStr>,
+}
+
+enum BuilderOptionValue {
+    Flag,
+    Value(Box<syn::Expr>),
+    List(Punctuated<BuilderOption, Token![,]>),
+}
+
+struct BuilderOption {
+    key: syn::Ident,
+    value: BuilderOptionValue,
+}
+
+impl Parse for BuilderOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: syn::Ident = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+
+            BuilderOptionValue::Value(input.parse()?)
+        } else if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+
+            BuilderOptionValue::List(content.parse_terminated(BuilderOption::parse)?)
+        } else if input.is_empty() || input.peek(Token![,]) {
+            BuilderOptionValue::Flag
+        } else {
+            return Err(input.error(format!("expected `=` after `{}`", key)));
+        };
+
+        Ok(BuilderOption { key, value })
+    }
+}
+
+impl BuilderOption {
+    fn unknown(&self) -> syn::Error {
+        syn::Error::new(self.key.span(), format!("unknown builder attribute `{}`", self.key))
+    }
+
+    fn unsupported_on_enum(&self) -> syn::Error {
+        syn::Error::new(
+            self.key.span(),
+            format!("builder attribute `{}` is not supported on enums", self.key),
+        )
+    }
+
+    fn flag(&self) -> syn::Result<()> {
+        match &self.value {
+            BuilderOptionValue::Flag => Ok(()),
+            _ => Err(syn::Error::new(
+                self.key.span(),
+                format!("expected `{}` without a value", self.key),
+            )),
+        }
+    }
+
+    fn value(&self, expected: &str) -> syn::Result<&syn::Expr> {
+        match &self.value {
+            BuilderOptionValue::Value(value) => Ok(value),
+            _ => Err(syn::Error::new(
+                self.key.span(),
+                format!("expected `{} = {}`", self.key, expected),
+            )),
+        }
+    }
+
+    fn string(&self) -> syn::Result<syn::LitStr> {
+        match self.value("\"...\"")? {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(value),
+                ..
+            }) => Ok(value.clone()),
+            value => Err(syn::Error::new_spanned(value, "expected a string literal")),
+        }
+    }
+
+    fn boolean(&self) -> syn::Result<bool> {
+        match self.value("true` or `false")? {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Bool(value),
+                ..
+            }) => Ok(value.value),
+            value => Err(syn::Error::new_spanned(value, "expected `true` or `false`")),
+        }
+    }
+
+    fn closure(&self) -> syn::Result<syn::ExprClosure> {
+        let closure = match self.value("|...| ...")? {
+            syn::Expr::Closure(closure) => closure,
+            value => return Err(syn::Error::new_spanned(value, "expected a closure")),
+        };
+
+        match closure.inputs.iter().find(|input| !matches!(input, syn::Pat::Type(_))) {
+            Some(input) => Err(syn::Error::new_spanned(
+                input,
+                "closure parameters need explicit types",
+            )),
+            None => Ok(closure.clone()),
+        }
+    }
+
+    fn list(&self) -> syn::Result<Vec<&BuilderOption>> {
+        match &self.value {
+            BuilderOptionValue::List(options) => check_duplicate_options(options.iter()),
+            _ => Err(syn::Error::new(
+                self.key.span(),
+                format!("expected `{}(...)`", self.key),
+            )),
+        }
+    }
+
+    fn identifier(&self) -> syn::Result<syn::Ident> {
+        self.string()?.parse()
+    }
+
+    fn path(&self) -> syn::Result<syn::Path> {
+        self.string()?.parse()
+    }
+
+    fn expression(&self) -> syn::Result<syn::Expr> {
+        self.string()?.parse()
+    }
+
+    fn ty(&self) -> syn::Result<syn::Type> {
+        self.string()?.parse()
+    }
+
+    fn visibility(&self) -> syn::Result<syn::Visibility> {
+        self.string()?.parse()
+    }
+}
+
+fn check_duplicate_options<'a, I>(options: I) -> syn::Result<Vec<&'a BuilderOption>>
+where
+    I: IntoIterator<Item = &'a BuilderOption>,
+{
+    let mut seen_keys = HashSet::new();
+
+    options
+        .into_iter()
+        .map(|option| {
+            if seen_keys.insert(option.key.to_string()) {
+                Ok(option)
+            } else {
+                Err(syn::Error::new(
+                    option.key.span(),
+                    format!("duplicate builder attribute `{}`", option.key),
+                ))
+            }
+        })
+        .collect()
+}
+
+fn parse_builder_options(attrs: &[syn::Attribute]) -> syn::Result<Vec<BuilderOption>> {
+    let mut options = Vec::new();
+
+    for attribute in attrs.iter().filter(|attribute| attribute.path.is_ident("builder")) {
+        options.extend(attribute.parse_args_with(Punctuated::<BuilderOption, Token![,]>::parse_terminated)?);
+    }
+
+    check_duplicate_options(&options)?;
+
+    Ok(options)
+}
+
+pub(crate) fn parse_struct_attributes(attrs: &[syn::Attribute], is_enum: bool) -> syn::Result<StructAttributes> {
+    let mut attributes = StructAttributes {
+        default: false,
+        pattern: BuilderPattern::Mutable,
+        into: false,
+        validate: None,
+        name: None,
+        vis: None,
+        setter_prefix: None,
+        typestate: false,
+        serde: false,
+        no_std: false,
+        doc: None,
+        context: None,
+        to_builder: false,
+    };
+    let mut pattern = None;
+    let mut serde = None;
+
+    for option in parse_builder_options(attrs)? {
+        match option.key.to_string().as_str() {
+            "default" | "name" | "validate" | "doc" | "to_builder" if is_enum => return Err(option.unsupported_on_enum()),
+            "default" => {
+                option.flag()?;
+                attributes.default = true;
+            }
+            "into" => {
+                option.flag()?;
+                attributes.into = true;
+            }
+            "validate" => attributes.validate = Some(option.path()?),
+            "name" => attributes.name = Some(option.identifier()?),
+            "vis" => attributes.vis = Some(option.visibility()?),
+            "doc" => attributes.doc = Some(option.string()?),
+            "context" => attributes.context = Some(option.ty()?),
+            "setter" => {
+                for setter_option in option.list()? {
+                    match setter_option.key.to_string().as_str() {
+                        "prefix" => attributes.setter_prefix = Some(setter_option.identifier()?),
+                        _ => return Err(setter_option.unknown()),
+                    }
+                }
+            }
+            "typestate" => {
+                option.flag()?;
+                attributes.typestate = true;
+            }
+            "no_std" => {
+                option.flag()?;
+                attributes.no_std = true;
+            }
+            "to_builder" => {
+                option.flag()?;
+                attributes.to_builder = true;
+            }
+            "serde" if !cfg!(feature = "serde") => {
+                return Err(syn::Error::new(
+                    option.key.span(),
+                    "builder attribute `serde` requires the `serde` feature of derive_builder",
+                ))
+            }
+            "serde" => {
+                option.flag()?;
+                attributes.serde = true;
+                serde = Some(option.key.clone());
+            }
+            "pattern" => {
+                let value = option.string()?;
+
+                attributes.pattern = match value.value().as_str() {
+                    "owned" => BuilderPattern::Owned,
+                    "mutable" => BuilderPattern::Mutable,
+                    "immutable" => BuilderPattern::Immutable,
+                    _ => {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            "expected `owned`, `mutable` or `immutable`",
+                        ))
+                    }
+                };
+                pattern = Some(value);
+            }
+            _ => return Err(option.unknown()),
+        }
+    }
+
+    if let (true, Some(serde)) = (attributes.typestate, serde) {
+        return Err(syn::Error::new(
+            serde.span(),
+            "typestate builders cannot derive serde traits",
+        ));
+    }
+
+    if attributes.typestate {
+        match (&attributes.pattern, pattern) {
+            (BuilderPattern::Owned, _) | (_, None) => attributes.pattern = BuilderPattern::Owned,
+            (_, Some(pattern)) => {
+                return Err(syn::Error::new(
+                    pattern.span(),
+                    "typestate builders only support the `owned` pattern",
+                ))
+            }
+        }
+    }
+
+    Ok(attributes)
+}
+
+pub(crate) fn parse_field_attributes(field: &syn::Field) -> syn::Result<FieldAttributes> {
+    let mut attributes = FieldAttributes {
+        each: None,
+        name: None,
+        default: None,
+        default_with: None,
+        fallible_default: false,
+        into: false,
+        validate: None,
+        vis: None,
+        setter_prefix: None,
+        setter_name: None,
+        skip_setter: false,
+        optional: false,
+        required: false,
+        strip_option: true,
+        transform: None,
+        sub_builder: false,
+        doc: None,
+    };
+
+    for option in parse_builder_options(&field.attrs)? {
+        match option.key.to_string().as_str() {
+            "each" => attributes.each = Some(option.identifier()?),
+            "name" => attributes.name = Some(option.identifier()?),
+            "default" => {
+                attributes.default = Some(match option.value {
+                    BuilderOptionValue::Flag => quote! { ::core::default::Default::default() },
+                    _ => {
+                        let expression = option.expression()?;
+
+                        quote! { #expression }
+                    }
+                });
+            }
+            "default_with" | "try_default_with" if attributes.default_with.is_some() => {
+                return Err(syn::Error::new(
+                    option.key.span(),
+                    "a field cannot have both `default_with` and `try_default_with`",
+                ))
+            }
+            "default_with" => attributes.default_with = Some(option.path()?),
+            "try_default_with" => {
+                attributes.default_with = Some(option.path()?);
+                attributes.fallible_default = true;
+            }
+            "into" => {
+                option.flag()?;
+                attributes.into = true;
+            }
+            "validate" => attributes.validate = Some(option.path()?),
+            "vis" => attributes.vis = Some(option.visibility()?),
+            "doc" => attributes.doc = Some(option.string()?),
+            "sub_builder" => {
+                option.flag()?;
+                attributes.sub_builder = true;
+            }
+            "optional" | "required" if attributes.optional || attributes.required => {
+                return Err(syn::Error::new(
+                    option.key.span(),
+                    "a field cannot be both `optional` and `required`",
+                ))
+            }
+            "optional" => {
+                option.flag()?;
+                attributes.optional = true;
+            }
+            "required" => {
+                option.flag()?;
+                attributes.required = true;
+            }
+            "setter" => {
+                for setter_option in option.list()? {
+                    match setter_option.key.to_string().as_str() {
+                        "prefix" => attributes.setter_prefix = Some(setter_option.identifier()?),
+                        "name" => attributes.setter_name = Some(setter_option.identifier()?),
+                        "skip" => {
+                            setter_option.flag()?;
+                            attributes.skip_setter = true;
+                        }
+                        "strip_option" => attributes.strip_option = setter_option.boolean()?,
+                        "transform" => attributes.transform = Some(setter_option.closure()?),
+                        _ => return Err(setter_option.unknown()),
+                    }
+                }
+            }
+            _ => return Err(option.unknown()),
+        }
+    }
+
+    Ok(attributes)
+}
@@ -1,208 +1,1479 @@
+mod attributes;
+
 use proc_macro::TokenStream;
 
-use quote::quote;
-use syn::{Data, DeriveInput, Fields, parse_macro_input, Type, PathSegment};
-use proc_macro2::TokenTree;
+use attributes::{parse_field_attributes, parse_struct_attributes, BuilderPattern, StructAttributes};
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, parse_macro_input, Type};
+
+impl BuilderPattern {
+    fn setter<F>(
+        &self,
+        vis: &syn::Visibility,
+        signature: proc_macro2::TokenStream,
+        arguments: proc_macro2::TokenStream,
+        where_clause: proc_macro2::TokenStream,
+        assign: F,
+    ) -> proc_macro2::TokenStream
+    where
+        F: Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+    {
+        match self {
+            BuilderPattern::Owned => {
+                let body = assign(quote! { self });
+
+                quote! {
+                    #[must_use]
+                    #vis fn #signature(mut self, #arguments) -> Self #where_clause {
+                        #body
+
+                        self
+                    }
+                }
+            }
+            BuilderPattern::Mutable => {
+                let body = assign(quote! { self });
+
+                quote! {
+                    #vis fn #signature(&mut self, #arguments) -> &mut Self #where_clause {
+                        #body
+
+                        self
+                    }
+                }
+            }
+            BuilderPattern::Immutable => {
+                let body = assign(quote! { __builder });
 
-fn get_type_without_ident_wrapped(ty: &Type, skipped_ident: String) -> Option<&Type> {
-    if let Type::Path(path) = ty {
-        let segments = &path.path.segments;
-        let segment = segments.last().unwrap();
-        let ident = get_first_level_indent_of_type(ty).unwrap();
+                quote! {
+                    #[must_use]
+                    #vis fn #signature(&self, #arguments) -> Self #where_clause {
+                        let mut __builder = ::core::clone::Clone::clone(self);
+                        #body
 
-        if ident == &skipped_ident {
-            if let syn::PathArguments::AngleBracketed(field) = &segment.arguments {
-                if let syn::GenericArgument::Type(nested_type) = field.args.last().unwrap() {
-                    return get_type_without_ident_wrapped(nested_type, skipped_ident);
+                        __builder
+                    }
                 }
             }
         }
+    }
 
-        Some(ty)
-    } else {
-        None
+    fn build_receiver(&self) -> proc_macro2::TokenStream {
+        match self {
+            BuilderPattern::Owned => quote! { self },
+            BuilderPattern::Mutable => quote! { &mut self },
+            BuilderPattern::Immutable => quote! { &self },
+        }
+    }
+
+    fn take_field(&self, field_name: &syn::Ident) -> proc_macro2::TokenStream {
+        match self {
+            BuilderPattern::Owned => quote! { self.#field_name },
+            BuilderPattern::Mutable => quote! { self.#field_name.take() },
+            BuilderPattern::Immutable => quote! { ::core::clone::Clone::clone(&self.#field_name) },
+        }
     }
 }
 
-fn get_first_segment_from_path(path: &syn::Path) -> &PathSegment {
-    let segments = &path.segments;
+struct BuilderTarget<'a> {
+    name: &'a syn::Ident,
+    constructor: proc_macro2::TokenStream,
+    description: String,
+    builder_struct_name: syn::Ident,
+    builder_method_name: syn::Ident,
+    generics: &'a syn::Generics,
+    fields: Vec<BuilderField<'a>>,
+    phantom: bool,
+    convertible: bool,
+}
 
-    segments.first().unwrap()
+struct BuilderField<'a> {
+    ident: syn::Ident,
+    member: syn::Member,
+    ty: &'a Type,
+    value_type: &'a Type,
+    is_option: bool,
+    is_vec: bool,
+    optional: bool,
+    strip_option: bool,
+    transform: Option<syn::ExprClosure>,
+    sub_builder: Option<Type>,
+    each: Option<syn::Ident>,
+    default: Option<proc_macro2::TokenStream>,
+    default_with: Option<syn::Path>,
+    fallible_default: bool,
+    into: bool,
+    validate: Option<syn::Path>,
+    vis: syn::Visibility,
+    setter_name: Option<syn::Ident>,
+    docs: Vec<syn::Attribute>,
 }
 
-fn get_first_level_indent_of_type(ty: &Type) -> Option<&syn::Ident> {
-    if let Type::Path(path) = ty {
-        Some(&get_first_segment_from_path(&path.path).ident)
-    } else {
-        None
+impl BuilderField<'_> {
+    fn is_required(&self, struct_attributes: &StructAttributes) -> bool {
+        !struct_attributes.default
+            && self.default.is_none()
+            && self.each.is_none()
+            && self.setter_name.is_some()
+            && self.sub_builder.is_none()
+            && !self.is_option
+            && !self.is_vec
+            && !self.optional
     }
 }
 
-fn parse_field_attribute_and_get_identifier(field: &syn::Field) -> Option<TokenTree> {
-    if field.attrs.len() <= 0 {
+fn get_wrapped_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+
+    if segment.ident != wrapper {
         return None;
     }
 
-    let attribute = field.attrs.get(0).unwrap();
-    let segment = get_first_segment_from_path(&attribute.path);
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => match &arguments.args[0] {
+            syn::GenericArgument::Type(wrapped_type) => Some(wrapped_type),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn get_collection_item_types(ty: &Type) -> Option<Vec<&Type>> {
+    let segment = if let Type::Path(path) = ty {
+        path.path.segments.last()?
+    } else {
+        return None;
+    };
+    let arity = match segment.ident.to_string().as_str() {
+        "Vec" | "VecDeque" | "LinkedList" | "BinaryHeap" | "HashSet" | "BTreeSet" => 1,
+        "HashMap" | "BTreeMap" => 2,
+        _ => return None,
+    };
+    let arguments = if let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments {
+        &arguments.args
+    } else {
+        return None;
+    };
+    let item_types: Vec<_> = arguments
+        .iter()
+        .filter_map(|argument| match argument {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .take(arity)
+        .collect();
 
-    if segment.ident != "builder" {
-        panic!("Only builder attribute supported!");
+    if item_types.len() == arity {
+        Some(item_types)
+    } else {
+        None
     }
+}
 
-    if let proc_macro2::TokenTree::Group(group) = attribute.tokens.clone().into_iter().next().unwrap() {
-        let mut tokens = group.stream().into_iter();
+fn get_field_variant(prefix: &str, field_name: &syn::Ident) -> syn::Ident {
+    let camel_case: String = field_name
+        .unraw()
+        .to_string()
+        .split('_')
+        .map(|word| {
+            let mut characters = word.chars();
 
-        if tokens.next().unwrap().to_string() != "each" {
-            proc_macro_error::abort!(attribute, "expected `builder(each = \"...\")`");
-        }
+            match characters.next() {
+                Some(first) => first.to_uppercase().chain(characters).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    format_ident!("{}{}", prefix, camel_case)
+}
 
-        assert_eq!(tokens.next().unwrap().to_string(), "=");
+fn get_field_local(field_name: &syn::Ident) -> syn::Ident {
+    format_ident!("__field_{}", field_name)
+}
+
+fn get_missing_field_variant(field_name: &syn::Ident) -> syn::Ident {
+    get_field_variant("Missing", field_name)
+}
+
+fn get_invalid_field_variant(field_name: &syn::Ident) -> syn::Ident {
+    get_field_variant("Invalid", field_name)
+}
+
+fn get_nested_field_variant(field_name: &syn::Ident) -> syn::Ident {
+    get_field_variant("Nested", field_name)
+}
+
+fn get_default_field_variant(field_name: &syn::Ident) -> syn::Ident {
+    get_field_variant("Default", field_name)
+}
 
-        Some(tokens.next().unwrap())
+fn get_setter_argument(
+    argument_name: &syn::Ident,
+    argument_type: &Type,
+    into: bool,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    if into {
+        (
+            quote! { #argument_name: impl ::core::convert::Into<#argument_type> },
+            quote! { ::core::convert::Into::into(#argument_name) },
+        )
     } else {
-        None
+        (
+            quote! { #argument_name: #argument_type },
+            quote! { #argument_name },
+        )
+    }
+}
+
+fn get_sub_builder_type(ty: &Type) -> syn::Result<Type> {
+    let mut sub_builder_type = ty.clone();
+
+    match &mut sub_builder_type {
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last_mut().unwrap();
+
+            segment.ident = format_ident!("{}Builder", segment.ident);
+
+            Ok(sub_builder_type)
+        }
+        _ => Err(syn::Error::new_spanned(
+            ty,
+            "`sub_builder` requires the field type to be a struct deriving Builder",
+        )),
     }
 }
 
-#[proc_macro_error::proc_macro_error]
+fn get_generic_arguments(generics: &syn::Generics) -> Vec<proc_macro2::TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+
+                quote! { #ident }
+            }
+            syn::GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+
+                quote! { #lifetime }
+            }
+            syn::GenericParam::Const(param) => {
+                let ident = &param.ident;
+
+                quote! { #ident }
+            }
+        })
+        .collect()
+}
+
+fn get_builder_visibility(struct_attributes: &StructAttributes) -> syn::Visibility {
+    struct_attributes
+        .vis
+        .clone()
+        .unwrap_or_else(|| syn::parse_quote! { pub })
+}
+
+fn get_builder_fields<'a>(
+    fields: &'a Fields,
+    struct_attributes: &StructAttributes,
+) -> syn::Result<Vec<BuilderField<'a>>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let attributes = parse_field_attributes(field)?;
+            let member = match &field.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(syn::Index::from(index)),
+            };
+            let ident = match (attributes.name, &field.ident) {
+                (Some(name), _) => name,
+                (None, Some(ident)) => ident.clone(),
+                (None, None) => format_ident!("_{}", index),
+            };
+
+            let setter_name = if attributes.skip_setter {
+                None
+            } else if let Some(setter_name) = attributes.setter_name {
+                Some(setter_name)
+            } else if let Some(prefix) = attributes.setter_prefix.or_else(|| struct_attributes.setter_prefix.clone()) {
+                Some(format_ident!("{}_{}", prefix, ident))
+            } else {
+                Some(ident.clone())
+            };
+
+            let option_type = if attributes.required {
+                None
+            } else {
+                get_wrapped_type(&field.ty, "Option")
+            };
+            let value_type = option_type.unwrap_or(&field.ty);
+
+            if attributes.each.is_some() && !matches!(value_type, Type::Path(_)) {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "`each` requires the field to be a collection",
+                ));
+            }
+
+            if let (Some(transform), Some(_)) = (&attributes.transform, &attributes.each) {
+                return Err(syn::Error::new_spanned(
+                    transform,
+                    "`transform` cannot be combined with `each`",
+                ));
+            }
+
+            let sub_builder = if !attributes.sub_builder {
+                None
+            } else if attributes.each.is_some() || attributes.transform.is_some() || attributes.validate.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "`sub_builder` cannot be combined with `each`, `transform` or `validate`",
+                ));
+            } else if attributes.fallible_default {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "`sub_builder` cannot be combined with `try_default_with`",
+                ));
+            } else if option_type.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "`sub_builder` is not supported on `Option` fields",
+                ));
+            } else if let BuilderPattern::Immutable = struct_attributes.pattern {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "`sub_builder` is not supported with the immutable builder pattern",
+                ));
+            } else {
+                Some(get_sub_builder_type(&field.ty)?)
+            };
+
+            let builder_field = BuilderField {
+                ident,
+                member,
+                ty: &field.ty,
+                value_type,
+                is_option: option_type.is_some(),
+                is_vec: !attributes.required && get_wrapped_type(&field.ty, "Vec").is_some(),
+                optional: attributes.optional,
+                strip_option: attributes.strip_option,
+                transform: attributes.transform,
+                sub_builder,
+                each: attributes.each,
+                default: attributes.default,
+                default_with: attributes.default_with,
+                fallible_default: attributes.fallible_default,
+                into: attributes.into || struct_attributes.into,
+                validate: attributes.validate,
+                vis: attributes.vis.unwrap_or_else(|| get_builder_visibility(struct_attributes)),
+                setter_name,
+                docs: match attributes.doc {
+                    Some(doc) => vec![syn::parse_quote! { #[doc = #doc] }],
+                    None => field
+                        .attrs
+                        .iter()
+                        .filter(|attribute| attribute.path.is_ident("doc"))
+                        .cloned()
+                        .collect(),
+                },
+            };
+
+            if let (Some(default_with), None) = (&builder_field.default_with, &struct_attributes.context) {
+                return Err(syn::Error::new_spanned(
+                    default_with,
+                    "`default_with` requires a `context` type on the struct",
+                ));
+            }
+
+            match (&builder_field.transform, &builder_field.default_with) {
+                (Some(transform), _) if struct_attributes.typestate && builder_field.is_required(struct_attributes) => {
+                    Err(syn::Error::new_spanned(
+                        transform,
+                        "`transform` is not supported on required fields of typestate builders",
+                    ))
+                }
+                (_, Some(default_with))
+                    if struct_attributes.typestate && builder_field.is_required(struct_attributes) =>
+                {
+                    Err(syn::Error::new_spanned(
+                        default_with,
+                        "`default_with` is not supported on required fields of typestate builders",
+                    ))
+                }
+                _ => Ok(builder_field),
+            }
+        })
+        .collect()
+}
+
 #[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: TokenStream) -> TokenStream {
     let parsed_ast = parse_macro_input!(input as DeriveInput);
+
+    expand_builder(&parsed_ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+// A word starts at a capital following a lowercase letter or digit, or at the
+// last capital of a run followed by a lowercase letter, so that `HTTPPost`
+// becomes `http_post`.
+fn get_snake_case(ident: &syn::Ident) -> String {
+    let characters: Vec<char> = ident.unraw().to_string().chars().collect();
+    let mut snake_case = String::new();
+
+    for (index, &character) in characters.iter().enumerate() {
+        if character.is_uppercase() && index > 0 {
+            let previous = characters[index - 1];
+            let next = characters.get(index + 1);
+
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+            {
+                snake_case.push('_');
+            }
+        }
+
+        snake_case.extend(character.to_lowercase());
+    }
+
+    snake_case
+}
+
+fn expand_builder(parsed_ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = &parsed_ast.ident;
-    let builder_struct_name = format!("{}Builder", name);
-    let builder_struct_name = syn::Ident::new(&builder_struct_name, name.span());
-    let data = parsed_ast.data;
+    let is_enum = matches!(parsed_ast.data, Data::Enum(_));
+    let struct_attributes = parse_struct_attributes(&parsed_ast.attrs, is_enum)?;
 
-    let fields = if let Data::Struct(ref data_struct) = data {
-        if let Fields::Named(ref named_fields) = data_struct.fields {
-            &named_fields.named
-        } else {
-            todo!();
+    match &parsed_ast.data {
+        Data::Struct(data_struct) => {
+            let target = BuilderTarget {
+                name,
+                constructor: quote! { #name },
+                description: format!("a `{}`", name),
+                builder_struct_name: struct_attributes
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format_ident!("{}Builder", name)),
+                builder_method_name: format_ident!("builder"),
+                generics: &parsed_ast.generics,
+                fields: get_builder_fields(&data_struct.fields, &struct_attributes)?,
+                phantom: false,
+                convertible: true,
+            };
+
+            Ok(expand_target_builder(&target, &struct_attributes))
+        }
+        Data::Enum(data_enum) => {
+            let mut tokens = proc_macro2::TokenStream::new();
+
+            for variant in &data_enum.variants {
+                if let Some(attribute) = variant.attrs.iter().find(|attribute| attribute.path.is_ident("builder")) {
+                    return Err(syn::Error::new_spanned(
+                        attribute,
+                        "builder attributes go on the enum or on the fields of its variants",
+                    ));
+                }
+
+                if let Fields::Unit = variant.fields {
+                    continue;
+                }
+
+                let variant_name = &variant.ident;
+                let target = BuilderTarget {
+                    name,
+                    constructor: quote! { #name::#variant_name },
+                    description: format!("the `{}::{}` variant", name, variant_name),
+                    builder_struct_name: format_ident!("{}{}Builder", name, variant_name),
+                    builder_method_name: format_ident!("{}_builder", get_snake_case(variant_name)),
+                    generics: &parsed_ast.generics,
+                    fields: get_builder_fields(&variant.fields, &struct_attributes)?,
+                    phantom: true,
+                    convertible: false,
+                };
+
+                tokens.extend(expand_target_builder(&target, &struct_attributes));
+            }
+
+            Ok(tokens)
         }
+        Data::Union(data_union) => Err(syn::Error::new_spanned(
+            data_union.union_token,
+            "Builder can only be derived for structs and enums",
+        )),
+    }
+}
+
+fn expand_target_builder(target: &BuilderTarget, struct_attributes: &StructAttributes) -> proc_macro2::TokenStream {
+    let name = target.name;
+    let constructor = &target.constructor;
+    let builder_struct_name = &target.builder_struct_name;
+    let builder_method_name = &target.builder_method_name;
+    let builder_error_name = format_ident!("{}Error", builder_struct_name);
+    let builder_vis = get_builder_visibility(struct_attributes);
+    let generics = target.generics;
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let fields = &target.fields;
+    let (alloc, error_trait) = if struct_attributes.no_std {
+        (quote! { ::alloc }, quote! { ::core::error::Error })
     } else {
-        todo!();
+        (quote! { ::std }, quote! { ::std::error::Error })
     };
+    let phantom_types: Vec<_> = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
 
-    let extra_builder_methods = fields
+                Some(quote! { #ident })
+            }
+            syn::GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+
+                Some(quote! { &#lifetime () })
+            }
+            syn::GenericParam::Const(_) => None,
+        })
+        .collect();
+    let typestate_fields: Vec<_> = fields
         .iter()
-        .filter(|field| field.attrs.len() > 0)
-        .map(|field| {
-            if let Some(argument) = parse_field_attribute_and_get_identifier(&field) {
-                let field_name = field.ident.as_ref().unwrap();
-                let field_type = get_type_without_ident_wrapped(&field.ty, "Option".to_string()).unwrap();
+        .filter(|field| struct_attributes.typestate && field.is_required(struct_attributes))
+        .collect();
+    // Typestate fields are stored as their state parameter, so the struct's
+    // own parameters may be left unused by the builder's fields.
+    let has_phantom = (target.phantom || !typestate_fields.is_empty()) && !phantom_types.is_empty();
+    let phantom_value = if has_phantom {
+        quote! { __phantom: ::core::marker::PhantomData, }
+    } else {
+        quote! {}
+    };
+    let phantom_moved = if has_phantom {
+        quote! { __phantom: self.__phantom, }
+    } else {
+        quote! {}
+    };
 
-                match get_first_level_indent_of_type(field_type) {
-                    Some(value) => assert_eq!("Vec", value.to_string()),
-                    _ => panic!("Only vectors are allowed with each macro!"),
-                };
+    let is_typestate_field = |field: &BuilderField| typestate_fields.iter().any(|typestate_field| typestate_field.ident == field.ident);
+    let state_params: Vec<_> = typestate_fields
+        .iter()
+        .map(|field| get_field_variant("__", &field.ident))
+        .collect();
+    let generic_arguments = get_generic_arguments(generics);
+    let builder_type = |states: &[proc_macro2::TokenStream]| {
+        quote! { #builder_struct_name<#(#generic_arguments,)* #(#states),*> }
+    };
+    let mut builder_generics = generics.clone();
+    let mut setters_generics = generics.clone();
 
-                let field_type = get_type_without_ident_wrapped(field_type, "Vec".to_string()).unwrap();
-                let parsed_argument = syn::Ident::new(
-                    &argument.to_string().replace('"', ""),
-                    proc_macro2::Span::call_site(),
-                );
+    for state_param in &state_params {
+        builder_generics.params.push(syn::parse_quote! { #state_param = () });
+        setters_generics.params.push(syn::parse_quote! { #state_param });
+    }
 
-                quote! {
-                    pub fn #parsed_argument(&mut self, value: #field_type) -> &mut Self {
-                        self.#field_name
-                            .get_or_insert(vec![])
-                            .push(value);
+    let (setters_impl_generics, _, _) = setters_generics.split_for_impl();
+    let setters_builder_type = builder_type(
+        &state_params
+            .iter()
+            .map(|state_param| quote! { #state_param })
+            .collect::<Vec<_>>(),
+    );
+    let built_builder_type = builder_type(
+        &typestate_fields
+            .iter()
+            .map(|field| {
+                let field_type = field.ty;
 
-                        self
+                quote! { #field_type }
+            })
+            .collect::<Vec<_>>(),
+    );
+    let empty_builder_type = builder_type(
+        &typestate_fields
+            .iter()
+            .map(|_| quote! { () })
+            .collect::<Vec<_>>(),
+    );
+
+    let extra_builder_methods = fields
+        .iter()
+        .filter(|field| field.setter_name.is_some())
+        .filter_map(|field| field.each.as_ref().map(|each| (field, each)))
+        .map(|(field, each)| {
+            let field_name = &field.ident;
+            let collection_type = field.value_type;
+            let (signature, arguments, item, where_clause) = match get_collection_item_types(collection_type).as_deref() {
+                Some([item_type]) => {
+                    let (argument, value) = get_setter_argument(&format_ident!("value"), item_type, field.into);
+
+                    (quote! { #each }, argument, value, quote! {})
+                }
+                Some([key_type, value_type]) => {
+                    let (key_argument, key) = get_setter_argument(&format_ident!("key"), key_type, field.into);
+                    let (value_argument, value) = get_setter_argument(&format_ident!("value"), value_type, field.into);
+
+                    (
+                        quote! { #each },
+                        quote! { #key_argument, #value_argument },
+                        quote! { (#key, #value) },
+                        quote! {},
+                    )
+                }
+                _ => (
+                    quote! { #each<__Item> },
+                    quote! { value: __Item },
+                    quote! { value },
+                    quote! { where #collection_type: ::core::iter::Extend<__Item> },
+                ),
+            };
+
+            let doc = format!("Adds an item to `{}`.", field_name.unraw());
+            let setter = struct_attributes.pattern.setter(&field.vis, signature, arguments, where_clause, |receiver| {
+                let collection = if field.is_option {
+                    quote! {
+                        #receiver.#field_name
+                            .get_or_insert(::core::option::Option::None)
+                            .get_or_insert_with(::core::default::Default::default)
                     }
+                } else {
+                    quote! {
+                        #receiver.#field_name.get_or_insert_with(::core::default::Default::default)
+                    }
+                };
+
+                quote! {
+                    ::core::iter::Extend::extend(#collection, ::core::iter::once(#item));
                 }
-            } else {
-                quote! {}
+            });
+
+            quote! {
+                #[doc = #doc]
+                #setter
             }
         });
+    let serde = struct_attributes.serde;
+    let phantom_field = if has_phantom && serde {
+        quote! {
+            #[serde(skip)]
+            __phantom: ::core::marker::PhantomData<fn() -> (#(#phantom_types,)*)>,
+        }
+    } else if has_phantom {
+        quote! { __phantom: ::core::marker::PhantomData<fn() -> (#(#phantom_types,)*)>, }
+    } else {
+        quote! {}
+    };
     let builder_fields = fields.iter().map(|field| {
-        let field_name = field.ident.as_ref().unwrap();
-        let field_type = &field.ty;
-        let ident = get_first_level_indent_of_type(field_type).unwrap();
+        let field_name = &field.ident;
+        let field_type = field.ty;
+        let serde_attribute = if !serde {
+            quote! {}
+        } else if field.transform.is_some() {
+            quote! { #[serde(skip)] }
+        } else {
+            quote! { #[serde(default, skip_serializing_if = "::core::option::Option::is_none")] }
+        };
+
+        if let Some(index) = typestate_fields.iter().position(|typestate_field| typestate_field.ident == field.ident) {
+            let state_param = &state_params[index];
 
-        if ident == "Option" {
             quote! {
-                #field_name: #field_type
+                #field_name: #state_param
+            }
+        } else if let Some(sub_builder) = &field.sub_builder {
+            quote! {
+                #serde_attribute
+                #field_name: ::core::option::Option<#sub_builder>
+            }
+        } else if field.transform.is_some() {
+            quote! {
+                #serde_attribute
+                #field_name: ::core::option::Option<::core::result::Result<#field_type, #alloc::string::String>>
             }
         } else {
             quote! {
-                #field_name: std::option::Option<#field_type>
+                #serde_attribute
+                #field_name: ::core::option::Option<#field_type>
             }
         }
     });
     let builder_fields_empty = fields.iter().map(|field| {
-        let field_name = field.ident.as_ref().unwrap();
+        let field_name = &field.ident;
 
-        quote! {
-            #field_name: std::option::Option::None
+        if is_typestate_field(field) {
+            quote! {
+                #field_name: ()
+            }
+        } else {
+            quote! {
+                #field_name: ::core::option::Option::None
+            }
         }
     });
-    let builder_fields_methods = fields.iter().map(|field| {
-        let field_name = field.ident.as_ref().unwrap();
-        let field_type = get_type_without_ident_wrapped(&field.ty, "Option".to_string()).unwrap();
+    let typestate_transition = |field: &BuilderField, state: proc_macro2::TokenStream, value: proc_macro2::TokenStream| {
+        let field_name = &field.ident;
+        let index = typestate_fields
+            .iter()
+            .position(|typestate_field| typestate_field.ident == field.ident)
+            .unwrap();
+        let mut states: Vec<_> = state_params
+            .iter()
+            .map(|state_param| quote! { #state_param })
+            .collect();
+        states[index] = state;
+        let next_builder_type = builder_type(&states);
+        let moved_fields = fields.iter().map(|other_field| {
+            let other_field_name = &other_field.ident;
 
-        if let Some(argument) = parse_field_attribute_and_get_identifier(&field) {
-            if field_name == &argument.to_string().replace('"', "") {
-                return quote! {};
+            if other_field_name == field_name {
+                quote! { #field_name: #value }
+            } else {
+                quote! { #other_field_name: self.#other_field_name }
             }
+        });
+
+        (
+            next_builder_type,
+            quote! {
+                #builder_struct_name {
+                    #(#moved_fields,)*
+                    #phantom_moved
+                }
+            },
+        )
+    };
+    let builder_fields_methods = fields.iter().map(|field| {
+        let field_name = &field.ident;
+        let vis = &field.vis;
+        let setter_name = match &field.setter_name {
+            Some(setter_name) => setter_name,
+            None => return quote! {},
+        };
+        let clear_name = format_ident!("clear_{}", field_name);
+        let strip_option = field.is_option && field.strip_option;
+        let setter_type = if strip_option { field.value_type } else { field.ty };
+        let (argument, value) = get_setter_argument(field_name, setter_type, field.into);
+        let has_setter = field.each.as_ref() != Some(setter_name);
+        let setter_docs = if field.docs.is_empty() {
+            let doc = if field.sub_builder.is_some() {
+                format!("Returns the builder for `{}`.", field_name.unraw())
+            } else {
+                format!("Sets `{}`.", field_name.unraw())
+            };
+
+            quote! { #[doc = #doc] }
+        } else {
+            let docs = &field.docs;
+
+            quote! { #(#docs)* }
+        };
+        let option_setter_doc = format!("Sets `{}` from an `Option`, keeping an explicit `None`.", field_name.unraw());
+        let clear_doc = format!("Clears `{}`, as if it had never been set.", field_name.unraw());
+
+        if is_typestate_field(field) {
+            let (next_builder_type, next_builder) = typestate_transition(field, quote! { #setter_type }, value);
+            let (cleared_builder_type, cleared_builder) = typestate_transition(field, quote! { () }, quote! { () });
+            let setter = if has_setter {
+                quote! {
+                    #setter_docs
+                    #vis fn #setter_name(self, #argument) -> #next_builder_type {
+                        #next_builder
+                    }
+                }
+            } else {
+                quote! {}
+            };
+
+            return quote! {
+                #setter
+
+                #[doc = #clear_doc]
+                #vis fn #clear_name(self) -> #cleared_builder_type {
+                    #cleared_builder
+                }
+            };
         }
 
-        quote! {
-            pub fn #field_name(&mut self, #field_name: #field_type) -> &mut Self {
-                self.#field_name = std::option::Option::Some(#field_name);
+        let setter = if let (true, Some(transform)) = (has_setter, &field.transform) {
+            let (arguments, argument_names): (Vec<_>, Vec<_>) = transform
+                .inputs
+                .iter()
+                .enumerate()
+                .filter_map(|(index, input)| match input {
+                    syn::Pat::Type(input) => {
+                        let argument_name = match &*input.pat {
+                            syn::Pat::Ident(pattern) => pattern.ident.clone(),
+                            _ => format_ident!("__argument{}", index),
+                        };
+                        let argument_type = &input.ty;
+
+                        Some((quote! { #argument_name: #argument_type }, argument_name))
+                    }
+                    _ => None,
+                })
+                .unzip();
+            let stored_value = if strip_option {
+                quote! { ::core::option::Option::Some(value) }
+            } else {
+                quote! { value }
+            };
+
+            struct_attributes.pattern.setter(
+                vis,
+                quote! { #setter_name },
+                quote! { #(#arguments),* },
+                quote! {},
+                |receiver| {
+                    quote! {
+                        #receiver.#field_name = ::core::option::Option::Some(
+                            (#transform)(#(#argument_names),*)
+                                .map(|value| #stored_value)
+                                .map_err(|error| #alloc::string::ToString::to_string(&error)),
+                        );
+                    }
+                },
+            )
+        } else if let (true, Some(sub_builder)) = (has_setter, &field.sub_builder) {
+            let field_type = field.ty;
+
+            // Edits through this accessor only stick when the nested builder
+            // uses the mutable pattern, which is all `sub_builder` supports.
+            // Any other pattern fails this check at the field's type.
+            let pattern_check = quote_spanned! {field_type.span()=>
+                let _: fn(&mut #sub_builder, #sub_builder) -> &mut #sub_builder = <#sub_builder>::merge;
+            };
+
+            quote! {
+                #vis fn #setter_name(&mut self) -> &mut #sub_builder {
+                    #pattern_check
+
+                    self.#field_name.get_or_insert_with(<#field_type>::builder)
+                }
+            }
+        } else if has_setter {
+            let stored_value = if strip_option {
+                quote! { ::core::option::Option::Some(#value) }
+            } else {
+                value
+            };
+
+            struct_attributes.pattern.setter(vis, quote! { #setter_name }, argument, quote! {}, |receiver| {
+                quote! {
+                    #receiver.#field_name = ::core::option::Option::Some(#stored_value);
+                }
+            })
+        } else {
+            quote! {}
+        };
+        let option_setter = if has_setter && strip_option {
+            let option_setter_name = format_ident!("{}_opt", setter_name);
+            let field_type = field.ty;
+            let value_type = field.value_type;
+            let (argument, value) = if field.into && field.transform.is_none() {
+                (
+                    quote! { #field_name: ::core::option::Option<impl ::core::convert::Into<#value_type>> },
+                    quote! { ::core::option::Option::map(#field_name, ::core::convert::Into::into) },
+                )
+            } else {
+                (quote! { #field_name: #field_type }, quote! { #field_name })
+            };
+            let stored_value = if field.transform.is_some() {
+                quote! { ::core::result::Result::Ok(#value) }
+            } else {
+                value
+            };
+
+            struct_attributes.pattern.setter(
+                vis,
+                quote! { #option_setter_name },
+                argument,
+                quote! {},
+                |receiver| {
+                    quote! {
+                        #receiver.#field_name = ::core::option::Option::Some(#stored_value);
+                    }
+                },
+            )
+        } else {
+            quote! {}
+        };
+        let clear = struct_attributes.pattern.setter(vis, quote! { #clear_name }, quote! {}, quote! {}, |receiver| {
+            quote! {
+                #receiver.#field_name = ::core::option::Option::None;
+            }
+        });
 
-                self
+        let setter = if has_setter {
+            quote! {
+                #setter_docs
+                #setter
+            }
+        } else {
+            quote! {}
+        };
+        let option_setter = if has_setter && strip_option {
+            quote! {
+                #[doc = #option_setter_doc]
+                #option_setter
             }
+        } else {
+            quote! {}
+        };
+
+        quote! {
+            #setter
+            #option_setter
+            #[doc = #clear_doc]
+            #clear
         }
     });
-    let constructor_arguments = fields.iter().map(|field| {
-        let field_name = field.ident.as_ref().unwrap();
-        let field_ident = get_first_level_indent_of_type(&field.ty).unwrap();
+    // Everything that can fail runs before anything is moved out of the builder,
+    // so a failed `build` on a `&mut self` builder leaves it untouched: the
+    // presence checks and validators look at the stored values by reference, and
+    // nested builders are checked through their own `__check` before any of
+    // them is built. The first half of the result is what `__check` runs.
+    let build_steps = |with_context: bool| {
+        let mut checks = Vec::new();
+        let mut validations = Vec::new();
+        let mut nested_checks = Vec::new();
+        let mut nested_builds = Vec::new();
+        let mut values = Vec::new();
+
+        for field in fields {
+            let field_name = &field.ident;
+            let member = &field.member;
+            let local_name = get_field_local(field_name);
+            let field_value = struct_attributes.pattern.take_field(field_name);
+
+            if is_typestate_field(field) {
+                values.push(quote! {
+                    let #local_name = #field_value;
+                });
+
+                continue;
+            }
 
-        if field_ident == "Option" {
+            let fallible_default = with_context && field.fallible_default;
+            let fallback = if let (true, Some(default_with)) = (fallible_default, &field.default_with) {
+                let variant = get_default_field_variant(field_name);
+
+                Some(quote! {
+                    #default_with(ctx)
+                        .map_err(|error| #builder_error_name::#variant(#alloc::string::ToString::to_string(&error)))?
+                })
+            } else if let (true, Some(default_with)) = (with_context, &field.default_with) {
+                Some(quote! { #default_with(ctx) })
+            } else if let Some(default) = &field.default {
+                Some(default.clone())
+            } else if struct_attributes.default {
+                Some(quote! { __default.#member })
+            } else if field.is_option {
+                Some(quote! { ::core::option::Option::None })
+            } else if field.is_vec {
+                Some(quote! { #alloc::vec::Vec::new() })
+            } else if field.each.is_some() || field.setter_name.is_none() || field.optional {
+                Some(quote! { ::core::default::Default::default() })
+            } else {
+                None
+            };
+            let validate = |value: proc_macro2::TokenStream| {
+                field.validate.as_ref().map(|validate| {
+                    let variant = get_invalid_field_variant(field_name);
+
+                    quote! {
+                        #validate(#value)
+                            .map_err(|error| #builder_error_name::#variant(#alloc::string::ToString::to_string(&error)))
+                    }
+                })
+            };
+
+            if let Some(sub_builder) = &field.sub_builder {
+                let variant = get_nested_field_variant(field_name);
+                let field_type = field.ty;
+                let nested_error = quote! {
+                    |error| #builder_error_name::#variant(#alloc::string::ToString::to_string(&error))
+                };
+                let has_default = field.default.is_some()
+                    || struct_attributes.default
+                    || (with_context && field.default_with.is_some());
+                let build_unset = match &fallback {
+                    Some(fallback) if has_default => quote! { ::core::result::Result::Ok(#fallback) },
+                    _ => quote! { __build(&mut <#field_type>::builder()) },
+                };
+                // Spanned at the field so that a nested builder not using the
+                // mutable pattern is reported there as a type mismatch.
+                let build = quote_spanned! {field_type.span()=>
+                    let __build: fn(&mut #sub_builder) -> ::core::result::Result<#field_type, _> = <#sub_builder>::build;
+                };
+
+                nested_checks.push(if has_default {
+                    quote! {
+                        if let ::core::option::Option::Some(builder) = &self.#field_name {
+                            builder.__check().map_err(#nested_error)?;
+                        }
+                    }
+                } else {
+                    quote! {
+                        match &self.#field_name {
+                            ::core::option::Option::Some(builder) => builder.__check(),
+                            ::core::option::Option::None => <#field_type>::builder().__check(),
+                        }
+                        .map_err(#nested_error)?;
+                    }
+                });
+                nested_builds.push(quote! {
+                    let #local_name = {
+                        #build
+
+                        match #field_value {
+                            ::core::option::Option::Some(mut builder) => __build(&mut builder),
+                            ::core::option::Option::None => #build_unset,
+                        }
+                        .map_err(#nested_error)?
+                    };
+                });
+
+                continue;
+            }
+
+            let set_value = if field.transform.is_some() {
+                quote! { ::core::option::Option::Some(::core::result::Result::Ok(value)) }
+            } else {
+                quote! { ::core::option::Option::Some(value) }
+            };
+
+            if fallback.is_none() {
+                let variant = get_missing_field_variant(field_name);
+
+                checks.push(quote! {
+                    if self.#field_name.is_none() {
+                        return ::core::result::Result::Err(#builder_error_name::#variant);
+                    }
+                });
+            }
+
+            if field.transform.is_some() {
+                let variant = get_invalid_field_variant(field_name);
+
+                checks.push(quote! {
+                    if let ::core::option::Option::Some(::core::result::Result::Err(error)) = &self.#field_name {
+                        return ::core::result::Result::Err(#builder_error_name::#variant(::core::clone::Clone::clone(error)));
+                    }
+                });
+            }
+
+            let validation = validate(quote! { value });
+
+            match (&fallback, &validation) {
+                (Some(fallback), validation) if validation.is_some() || fallible_default => {
+                    // The fallback is computed up front so that it can be
+                    // validated, or fail, while the set value is still in the
+                    // builder.
+                    let fallback_name = format_ident!("__fallback_{}", field_name);
+                    let validation = validation.as_ref().map(|validation| {
+                        quote! {
+                            if let #set_value = &self.#field_name {
+                                #validation?;
+                            }
+
+                            if let ::core::option::Option::Some(value) = &#fallback_name {
+                                #validation?;
+                            }
+                        }
+                    });
+
+                    validations.push(quote! {
+                        let #fallback_name = match self.#field_name {
+                            ::core::option::Option::Some(_) => ::core::option::Option::None,
+                            ::core::option::Option::None => ::core::option::Option::Some(#fallback),
+                        };
+
+                        #validation
+                    });
+                    values.push(quote! {
+                        let #local_name = match (#field_value, #fallback_name) {
+                            (#set_value, _) | (_, ::core::option::Option::Some(value)) => value,
+                            _ => ::core::unreachable!(),
+                        };
+                    });
+                }
+                (fallback, validation) => {
+                    let fallback = fallback.clone().unwrap_or_else(|| {
+                        let variant = get_missing_field_variant(field_name);
+
+                        quote! { return ::core::result::Result::Err(#builder_error_name::#variant) }
+                    });
+                    let invalid = field.transform.as_ref().map(|_| {
+                        let variant = get_invalid_field_variant(field_name);
+
+                        quote! {
+                            ::core::option::Option::Some(::core::result::Result::Err(error)) => {
+                                return ::core::result::Result::Err(#builder_error_name::#variant(error))
+                            }
+                        }
+                    });
+
+                    if let Some(validation) = validation {
+                        validations.push(quote! {
+                            if let #set_value = &self.#field_name {
+                                #validation?;
+                            }
+                        });
+                    }
+
+                    values.push(quote! {
+                        let #local_name = match #field_value {
+                            #set_value => value,
+                            #invalid
+                            ::core::option::Option::None => #fallback,
+                        };
+                    });
+                }
+            }
+        }
+
+        (
+            quote! {
+                #(#checks)*
+                #(#validations)*
+                #(#nested_checks)*
+            },
             quote! {
-                #field_name: self.#field_name.take()
+                #(#nested_builds)*
+                #(#values)*
+            },
+        )
+    };
+    let constructor_arguments: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let member = &field.member;
+            let local_name = get_field_local(&field.ident);
+
+            quote! {
+                #member: #local_name
             }
-        } else if field_ident == "Vec" {
+        })
+        .collect();
+    let struct_validation = struct_attributes.validate.as_ref().map(|validate| {
+        quote! {
+            #validate(&self)
+                .map_err(|error| #builder_error_name::Validation(#alloc::string::ToString::to_string(&error)))?;
+        }
+    });
+    let default_value = if struct_attributes.default {
+        quote! {
+            let __default: #name #type_generics = ::core::default::Default::default();
+        }
+    } else {
+        quote! {}
+    };
+    let required_fields: Vec<_> = fields
+        .iter()
+        .filter(|field| field.is_required(struct_attributes) && !is_typestate_field(field))
+        .collect();
+    let mut error_variants: Vec<_> = required_fields
+        .iter()
+        .map(|field| {
+            let variant = get_missing_field_variant(&field.ident);
+            let doc = format!("`{}` was not set.", field.ident.unraw());
+
             quote! {
-                #field_name: self.#field_name.take().or(std::option::Option::Some(vec![])).unwrap()
+                #[doc = #doc]
+                #variant
             }
-        } else {
-            let error = format!("{} is required", field_name);
+        })
+        .collect();
+    let mut error_messages: Vec<_> = required_fields
+        .iter()
+        .map(|field| {
+            let variant = get_missing_field_variant(&field.ident);
+            let message = format!("{} is required", field.ident.unraw());
 
             quote! {
-                #field_name: self.#field_name.take().ok_or(#error)?
+                #builder_error_name::#variant => f.write_str(#message)
+            }
+        })
+        .collect();
+
+    for field in fields
+        .iter()
+        .filter(|field| field.validate.is_some() || field.transform.is_some())
+    {
+        let variant = get_invalid_field_variant(&field.ident);
+        let message = format!("invalid {}: {{}}", field.ident.unraw());
+        let doc = format!("`{}` was rejected by its validator or setter transform.", field.ident.unraw());
+
+        error_variants.push(quote! {
+            #[doc = #doc]
+            #variant(#alloc::string::String)
+        });
+        error_messages.push(quote! {
+            #builder_error_name::#variant(ref message) => ::core::write!(f, #message, message)
+        });
+    }
+
+    for field in fields.iter().filter(|field| field.sub_builder.is_some()) {
+        let variant = get_nested_field_variant(&field.ident);
+        let message = format!("{}: {{}}", field.ident.unraw());
+        let doc = format!("The nested builder for `{}` failed to build.", field.ident.unraw());
+
+        error_variants.push(quote! {
+            #[doc = #doc]
+            #variant(#alloc::string::String)
+        });
+        error_messages.push(quote! {
+            #builder_error_name::#variant(ref message) => ::core::write!(f, #message, message)
+        });
+    }
+
+    for field in fields.iter().filter(|field| field.fallible_default) {
+        let variant = get_default_field_variant(&field.ident);
+        let message = format!("could not compute default for {}: {{}}", field.ident.unraw());
+        let doc = format!("The `try_default_with` provider for `{}` failed.", field.ident.unraw());
+
+        error_variants.push(quote! {
+            #[doc = #doc]
+            #variant(#alloc::string::String)
+        });
+        error_messages.push(quote! {
+            #builder_error_name::#variant(ref message) => ::core::write!(f, #message, message)
+        });
+    }
+
+    if struct_attributes.validate.is_some() {
+        error_variants.push(quote! {
+            /// The built value was rejected by the struct validator.
+            Validation(#alloc::string::String)
+        });
+        error_messages.push(quote! {
+            #builder_error_name::Validation(ref message) => ::core::write!(f, "validation failed: {}", message)
+        });
+    }
+
+    let to_builder = if struct_attributes.to_builder {
+        quote! {
+            impl #impl_generics #name #type_generics #where_clause {
+                /// Returns a builder with every field set from `self`.
+                #builder_vis fn to_builder(&self) -> #built_builder_type
+                where
+                    #name #type_generics: ::core::clone::Clone,
+                {
+                    ::core::convert::From::from(::core::clone::Clone::clone(self))
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let conversion = if target.convertible {
+        let value_fields = fields.iter().map(|field| {
+            let member = &field.member;
+            let local_name = get_field_local(&field.ident);
+
+            quote! { #member: #local_name }
+        });
+        let builder_values = fields.iter().map(|field| {
+            let field_name = &field.ident;
+            let local_name = get_field_local(field_name);
+
+            if is_typestate_field(field) {
+                quote! { #field_name: #local_name }
+            } else if field.sub_builder.is_some() {
+                quote! { #field_name: ::core::option::Option::Some(::core::convert::From::from(#local_name)) }
+            } else if field.transform.is_some() {
+                quote! { #field_name: ::core::option::Option::Some(::core::result::Result::Ok(#local_name)) }
+            } else {
+                quote! { #field_name: ::core::option::Option::Some(#local_name) }
+            }
+        });
+
+        quote! {
+            impl #impl_generics ::core::convert::From<#name #type_generics> for #built_builder_type #where_clause {
+                fn from(value: #name #type_generics) -> Self {
+                    let #name { #(#value_fields,)* } = value;
+
+                    #builder_struct_name {
+                        #(#builder_values,)*
+                        #phantom_value
+                    }
+                }
+            }
+
+            #to_builder
+        }
+    } else {
+        quote! {}
+    };
+    let build_receiver = struct_attributes.pattern.build_receiver();
+    let (build_checks, build_values) = build_steps(false);
+    let build_with = struct_attributes.context.as_ref().map(|context| {
+        let (checks, values) = build_steps(true);
+        let doc = format!("Builds {} like `build`, computing `default_with` defaults from `ctx`.", target.description);
+
+        quote! {
+            #[doc = #doc]
+            #[allow(unused_variables)]
+            #builder_vis fn build_with(
+                #build_receiver,
+                ctx: &#context,
+            ) -> ::core::result::Result<#name #type_generics, #builder_error_name> {
+                #struct_validation
+                #default_value
+                #checks
+                #values
+
+                ::core::result::Result::Ok(#constructor {
+                    #(#constructor_arguments,)*
+                })
             }
         }
     });
+    let mut builder_derives = if let BuilderPattern::Immutable = struct_attributes.pattern {
+        quote! { #[derive(::core::clone::Clone)] }
+    } else {
+        quote! {}
+    };
 
-    let tokens = quote!(
-        use std::error::Error;
+    if serde {
+        builder_derives.extend(quote! {
+            #[derive(::serde::Serialize, ::serde::Deserialize)]
+        });
+    }
+
+    let merge = if typestate_fields.is_empty() {
+        let merge = struct_attributes.pattern.setter(&builder_vis, quote! { merge }, quote! { other: Self }, quote! {}, |receiver| {
+            let merged_fields = fields.iter().map(|field| {
+                let field_name = &field.ident;
+
+                if let Some(sub_builder) = &field.sub_builder {
+                    let merge = quote_spanned! {field.ty.span()=>
+                        let merge: fn(&mut #sub_builder, #sub_builder) -> &mut #sub_builder = <#sub_builder>::merge;
+                    };
+
+                    return quote! {
+                        if let ::core::option::Option::Some(value) = other.#field_name {
+                            match &mut #receiver.#field_name {
+                                ::core::option::Option::Some(builder) => {
+                                    #merge
+
+                                    merge(builder, value);
+                                }
+                                ::core::option::Option::None => {
+                                    #receiver.#field_name = ::core::option::Option::Some(value);
+                                }
+                            }
+                        }
+                    };
+                }
+
+                quote! {
+                    if let ::core::option::Option::Some(value) = other.#field_name {
+                        #receiver.#field_name = ::core::option::Option::Some(value);
+                    }
+                }
+            });
 
-        pub struct #builder_struct_name {
+            quote! { #(#merged_fields)* }
+        });
+
+        quote! {
+            /// Sets every field that is set on `other`, replacing the current value.
+            /// Nested builders are merged field by field instead.
+            #merge
+        }
+    } else {
+        quote! {}
+    };
+
+    let description = &target.description;
+    let builder_doc = match &struct_attributes.doc {
+        Some(doc) => doc.value(),
+        None => format!("Builder for {}.", description),
+    };
+    let error_doc = format!("Error returned when building {} fails.", description);
+    let build_doc = format!("Builds {}, returning an error if a required field is missing.", description);
+    let builder_method_doc = format!("Returns an empty builder for {}.", description);
+
+    let tokens = quote!(
+        #[doc = #builder_doc]
+        #builder_derives
+        #builder_vis struct #builder_struct_name #builder_generics #where_clause {
             #(#builder_fields,)*
+            #phantom_field
         }
 
-        impl #builder_struct_name {
+        #[doc = #error_doc]
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+        #builder_vis enum #builder_error_name {
+            #(#error_variants,)*
+        }
+
+        impl ::core::fmt::Display for #builder_error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match *self {
+                    #(#error_messages,)*
+                }
+            }
+        }
+
+        impl #error_trait for #builder_error_name {}
+
+        impl #setters_impl_generics #setters_builder_type #where_clause {
             #(#builder_fields_methods)*
             #(#extra_builder_methods)*
+            #merge
+        }
+
+        impl #impl_generics #built_builder_type #where_clause {
+            #[doc = #build_doc]
+            #builder_vis fn build(#build_receiver) -> ::core::result::Result<#name #type_generics, #builder_error_name> {
+                #struct_validation
+                #default_value
+                #build_checks
+                #build_values
 
-            pub fn build(&mut self) -> std::result::Result<#name, std::boxed::Box<dyn Error>> {
-                Ok(#name {
+                ::core::result::Result::Ok(#constructor {
                     #(#constructor_arguments,)*
                 })
             }
+
+            #build_with
+
+            /// Runs every check `build` would, without moving anything out of
+            /// the builder. Used by outer builders holding this one as a
+            /// `sub_builder`.
+            #[doc(hidden)]
+            #[allow(unused_variables)]
+            #builder_vis fn __check(&self) -> ::core::result::Result<(), #builder_error_name> {
+                #struct_validation
+                #default_value
+                #build_checks
+
+                ::core::result::Result::Ok(())
+            }
         }
 
-        impl #name {
-           pub fn builder() -> #builder_struct_name {
+        impl #impl_generics #name #type_generics #where_clause {
+            #[doc = #builder_method_doc]
+            #builder_vis fn #builder_method_name() -> #empty_builder_type {
                 #builder_struct_name {
                     #(#builder_fields_empty,)*
+                    #phantom_value
                 }
             }
         }
+
+        #conversion
     );
 
-    tokens.into()
+    tokens
 }
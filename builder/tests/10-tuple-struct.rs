@@ -0,0 +1,40 @@
+// Tuple structs have no field names to derive setter names from, so each
+// positional field gets a setter named after its index (`_0`, `_1`, ...)
+// unless a name is supplied through #[builder(name = "...")].
+//
+// The same Option and Vec handling that applies to named fields applies to
+// positional ones as well.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command(
+    String,
+    #[builder(name = "args", each = "arg")] Vec<String>,
+    #[builder(name = "current_dir")] Option<String>,
+);
+
+fn main() {
+    let command = Command::builder()
+        ._0("cargo".to_owned())
+        .arg("build".to_owned())
+        .arg("--release".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.0, "cargo");
+    assert_eq!(command.1, vec!["build", "--release"]);
+    assert_eq!(command.2, None);
+
+    let command = Command::builder()
+        ._0("cargo".to_owned())
+        .args(vec!["test".to_owned()])
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.1, vec!["test"]);
+    assert_eq!(command.2, Some("..".to_owned()));
+
+    assert!(Command::builder().arg("build".to_owned()).build().is_err());
+}
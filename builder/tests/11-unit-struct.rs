@@ -0,0 +1,13 @@
+// A unit struct has nothing to set, but deriving Builder on it should still
+// produce a builder whose build() always succeeds.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Marker;
+
+fn main() {
+    let marker = Marker::builder().build().unwrap();
+
+    assert_eq!(marker, Marker);
+}
@@ -0,0 +1,38 @@
+// A failed build() reports which field was missing through a generated
+// `{Name}BuilderError` enum. Every required field gets its own `Missing*`
+// variant, so callers can match on it instead of inspecting a message.
+//
+// The error still implements Display and std::error::Error so it can be
+// propagated with `?` into a Box<dyn Error>.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+fn build_boxed() -> Result<Command, Box<dyn std::error::Error>> {
+    Ok(Command::builder().build()?)
+}
+
+fn main() {
+    let error = Command::builder()
+        .current_dir("..".to_owned())
+        .build()
+        .err()
+        .unwrap();
+
+    match error {
+        CommandBuilderError::MissingExecutable => {}
+    }
+
+    assert_eq!(error.to_string(), "executable is required");
+    assert_eq!(
+        build_boxed().err().unwrap().to_string(),
+        "executable is required",
+    );
+}
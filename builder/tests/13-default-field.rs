@@ -0,0 +1,81 @@
+// Fields marked #[builder(default)] fall back to Default::default() when they
+// are never set, and #[builder(default = "...")] falls back to the given
+// expression instead. Neither makes build() fail.
+//
+// Putting #[builder(default)] on the struct itself makes every field that was
+// left unset take its value from the struct's own Default impl. That still
+// works when one of the fields is itself called `default`.
+
+use derive_builder::Builder;
+
+fn default_jobs() -> u32 {
+    4
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(default)]
+    verbose: bool,
+    #[builder(default = "default_jobs()")]
+    jobs: u32,
+    #[builder(default = "Some(\"..\".to_owned())")]
+    current_dir: Option<String>,
+}
+
+mod config {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(default)]
+    pub struct Config {
+        pub default: u32,
+        pub name: String,
+        pub retries: u8,
+        pub tags: Vec<String>,
+    }
+
+    impl Default for Config {
+        fn default() -> Self {
+            Config {
+                default: 7,
+                name: "unnamed".to_owned(),
+                retries: 3,
+                tags: vec!["default".to_owned()],
+            }
+        }
+    }
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert!(!command.verbose);
+    assert_eq!(command.jobs, 4);
+    assert_eq!(command.current_dir, Some("..".to_owned()));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .verbose(true)
+        .jobs(1)
+        .current_dir("/".to_owned())
+        .build()
+        .unwrap();
+
+    assert!(command.verbose);
+    assert_eq!(command.jobs, 1);
+    assert_eq!(command.current_dir, Some("/".to_owned()));
+
+    assert!(Command::builder().build().is_err());
+
+    let config = config::Config::builder().retries(5).build().unwrap();
+
+    assert_eq!(config.default, 7);
+    assert_eq!(config.name, "unnamed");
+    assert_eq!(config.retries, 5);
+    assert_eq!(config.tags, vec!["default"]);
+}
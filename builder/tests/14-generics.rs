@@ -0,0 +1,83 @@
+// The builder carries over the generic parameters of the struct it builds:
+// lifetimes, type parameters with their bounds, const generics, defaults and
+// where-clauses all have to show up on the builder struct, its impl block and
+// the builder() constructor.
+
+mod request {
+    use derive_builder::Builder;
+
+    pub trait Serialize {
+        fn serialize(&self) -> String;
+    }
+
+    impl Serialize for u32 {
+        fn serialize(&self) -> String {
+            self.to_string()
+        }
+    }
+
+    #[derive(Builder)]
+    pub struct Request<'a, T: Serialize> {
+        pub path: &'a str,
+        pub body: &'a T,
+        pub headers: Vec<(&'a str, &'a str)>,
+    }
+}
+
+mod buffer {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    pub struct Buffer<const N: usize> {
+        pub data: [u8; N],
+        pub len: Option<usize>,
+    }
+}
+
+mod pair {
+    use derive_builder::Builder;
+    use std::fmt::Debug;
+
+    #[derive(Builder)]
+    pub struct Pair<K, V = String>
+    where
+        K: Debug + Clone,
+    {
+        pub key: K,
+        #[builder(each = "value")]
+        pub values: Vec<V>,
+    }
+}
+
+use request::Serialize;
+
+fn main() {
+    let body = 5u32;
+    let request = request::Request::builder()
+        .path("/index")
+        .body(&body)
+        .build()
+        .unwrap();
+
+    assert_eq!(request.path, "/index");
+    assert_eq!(request.body.serialize(), "5");
+    assert!(request.headers.is_empty());
+
+    let buffer = buffer::Buffer::<4>::builder()
+        .data([1, 2, 3, 4])
+        .build()
+        .unwrap();
+
+    assert_eq!(buffer.data, [1, 2, 3, 4]);
+    assert_eq!(buffer.len, None);
+
+    let pair: pair::Pair<u8> = pair::Pair::builder()
+        .key(1)
+        .value("one".to_owned())
+        .value("uno".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(pair.key, 1);
+    assert_eq!(pair.values, vec!["one", "uno"]);
+}
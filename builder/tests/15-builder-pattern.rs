@@ -0,0 +1,57 @@
+// The shape of the generated setters can be picked at the struct level with
+// #[builder(pattern = "...")]:
+//
+//   - "mutable" (the default) takes and returns `&mut Self`;
+//   - "owned" takes and returns the builder by value, so a builder can be
+//     passed through functions and build() consumes it;
+//   - "immutable" takes `&self` and returns a modified clone, leaving the
+//     original builder untouched so it can be built any number of times.
+
+mod owned {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(pattern = "owned")]
+    pub struct Command {
+        pub executable: String,
+        #[builder(each = "arg")]
+        pub args: Vec<String>,
+        pub current_dir: Option<String>,
+    }
+}
+
+mod immutable {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(pattern = "immutable")]
+    pub struct Command {
+        pub executable: String,
+        #[builder(each = "arg")]
+        pub args: Vec<String>,
+    }
+}
+
+fn release(builder: owned::CommandBuilder) -> owned::CommandBuilder {
+    builder.arg("--release".to_owned())
+}
+
+fn main() {
+    let builder = owned::Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned());
+    let command = release(builder).build().unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir, None);
+
+    let base = immutable::Command::builder().executable("cargo".to_owned());
+    let build = base.arg("build".to_owned());
+    let test = base.arg("test".to_owned());
+
+    assert_eq!(build.build().unwrap().args, vec!["build"]);
+    assert_eq!(build.build().unwrap().args, vec!["build"]);
+    assert_eq!(test.build().unwrap().args, vec!["test"]);
+    assert!(base.build().unwrap().args.is_empty());
+}
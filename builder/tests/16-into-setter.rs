@@ -0,0 +1,77 @@
+// Setters of fields marked #[builder(into)] accept anything convertible into
+// the field type, so a &str can be passed where the field is a String. The
+// one-at-a-time `each` setter of such a field converts its element the same
+// way, and so does the `_opt` setter of an optional field, which takes an
+// `Option` of anything convertible. A bare `None` passed to it needs its type
+// spelled out, as in `None::<&str>`.
+//
+// Putting #[builder(into)] on the struct applies it to every field.
+
+mod field {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    pub struct Command {
+        #[builder(into)]
+        pub executable: String,
+        #[builder(each = "arg", into)]
+        pub args: Vec<String>,
+        #[builder(into)]
+        pub current_dir: Option<String>,
+        pub jobs: u32,
+    }
+}
+
+mod whole_struct {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(into)]
+    pub struct Command {
+        pub executable: String,
+        pub jobs: u64,
+    }
+}
+
+fn main() {
+    let command = field::Command::builder()
+        .executable("cargo")
+        .arg("build")
+        .arg(String::from("--release"))
+        .current_dir("..")
+        .jobs(4)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir, Some("..".to_owned()));
+    assert_eq!(command.jobs, 4);
+
+    let command = field::Command::builder()
+        .executable("cargo")
+        .current_dir_opt(Some("/tmp"))
+        .jobs(1)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.current_dir, Some("/tmp".to_owned()));
+
+    let command = field::Command::builder()
+        .executable("cargo")
+        .current_dir_opt(None::<&str>)
+        .jobs(1)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.current_dir, None);
+
+    let command = whole_struct::Command::builder()
+        .executable("cargo")
+        .jobs(4u32)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.jobs, 4);
+}
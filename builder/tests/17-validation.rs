@@ -0,0 +1,74 @@
+// #[builder(validate = "path")] on the struct runs the given function against
+// the builder before anything is built, and on a field runs it against the
+// field's final value. Validators return a Result whose error is anything
+// displayable; a failure comes back from build() as
+// `{Name}BuilderError::Validation` for the struct-level hook and as
+// `{Name}BuilderError::Invalid{Field}` for a field-level one.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+#[builder(validate = "validate_command")]
+pub struct Command {
+    executable: String,
+    #[builder(validate = "validate_jobs", default = "1")]
+    jobs: u32,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+fn validate_command(builder: &CommandBuilder) -> Result<(), String> {
+    match &builder.executable {
+        Some(executable) if executable.is_empty() => Err("executable must not be empty".to_owned()),
+        _ => Ok(()),
+    }
+}
+
+fn validate_jobs(jobs: &u32) -> Result<(), &'static str> {
+    if *jobs == 0 {
+        Err("at least one job is needed")
+    } else {
+        Ok(())
+    }
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .jobs(2)
+        .arg("build".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.jobs, 2);
+    assert_eq!(command.args, vec!["build"]);
+
+    let error = Command::builder()
+        .executable(String::new())
+        .build()
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        CommandBuilderError::Validation("executable must not be empty".to_owned()),
+    );
+    assert_eq!(error.to_string(), "validation failed: executable must not be empty");
+
+    let error = Command::builder()
+        .executable("cargo".to_owned())
+        .jobs(0)
+        .build()
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        CommandBuilderError::InvalidJobs("at least one job is needed".to_owned()),
+    );
+    assert_eq!(error.to_string(), "invalid jobs: at least one job is needed");
+
+    assert_eq!(
+        Command::builder().build().unwrap_err(),
+        CommandBuilderError::MissingExecutable,
+    );
+}
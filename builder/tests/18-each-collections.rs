@@ -0,0 +1,71 @@
+// #[builder(each = "...")] is not limited to Vec. Sets and deques get a
+// one-at-a-time setter taking a single element, maps get one taking the key
+// and the value, and any other collection implementing Default and Extend
+// gets a setter that accepts whatever item type the collection can be
+// extended with.
+//
+// Fields with an `each` setter that are never touched build into an empty
+// collection.
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+#[derive(Default, Debug, PartialEq)]
+pub struct Flags(Vec<String>);
+
+impl Extend<&'static str> for Flags {
+    fn extend<I: IntoIterator<Item = &'static str>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(|flag| format!("--{}", flag)));
+    }
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "env")]
+    env: HashMap<String, String>,
+    #[builder(each = "define", into)]
+    defines: std::collections::BTreeMap<String, String>,
+    #[builder(each = "feature")]
+    features: HashSet<String>,
+    #[builder(each = "arg")]
+    args: VecDeque<String>,
+    #[builder(each = "flag")]
+    flags: Flags,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .env("RUST_LOG".to_owned(), "debug".to_owned())
+        .define("PROFILE", "release")
+        .feature("std".to_owned())
+        .feature("std".to_owned())
+        .arg("build".to_owned())
+        .arg("--release".to_owned())
+        .flag("verbose")
+        .build()
+        .unwrap();
+
+    let mut env = HashMap::new();
+    env.insert("RUST_LOG".to_owned(), "debug".to_owned());
+
+    let mut defines = BTreeMap::new();
+    defines.insert("PROFILE".to_owned(), "release".to_owned());
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.env, env);
+    assert_eq!(command.defines, defines);
+    assert_eq!(command.features.len(), 1);
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.flags, Flags(vec!["--verbose".to_owned()]));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+
+    assert!(command.env.is_empty());
+    assert!(command.features.is_empty());
+    assert_eq!(command.flags, Flags::default());
+}
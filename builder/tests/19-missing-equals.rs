@@ -0,0 +1,13 @@
+// Leaving out the `=` between an attribute key and its value is reported at
+// the value that follows the key.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each "arg")]
+    args: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// Attribute values are string literals; anything else is reported at the
+// value itself.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = arg)]
+    args: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// A one-at-a-time `each` setter only makes sense for a collection, so using
+// it on a field whose type cannot be one is reported at that type.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: &'static [String],
+}
+
+fn main() {}
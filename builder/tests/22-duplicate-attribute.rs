@@ -0,0 +1,14 @@
+// Giving the same attribute key twice, whether inside one #[builder(...)] or
+// across several, is reported at the second occurrence.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    #[builder(each = "argument")]
+    args: Vec<String>,
+}
+
+fn main() {}
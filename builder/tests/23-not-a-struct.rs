@@ -0,0 +1,11 @@
+// Builder cannot be derived for a union.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub union Number {
+    integer: u64,
+    float: f64,
+}
+
+fn main() {}
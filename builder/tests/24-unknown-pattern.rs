@@ -0,0 +1,12 @@
+// Struct level attribute values are checked as well: an unsupported builder
+// pattern is reported at the string naming it.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "borrowed")]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {}
@@ -0,0 +1,66 @@
+// The names and visibility of the generated items can be adjusted:
+//
+//   - #[builder(name = "...")] on the struct renames the builder (and with it
+//     the `{Builder}Error` type);
+//   - #[builder(vis = "...")] on the struct sets the visibility of the
+//     builder, and on a field that of the field's setters;
+//   - #[builder(setter(prefix = "..."))] prepends a prefix to setter names,
+//     either for every field or for a single one;
+//   - #[builder(setter(name = "..."))] renames a single setter;
+//   - #[builder(setter(skip))] leaves out the setter entirely, and the field
+//     is built from its default.
+
+mod command {
+    use derive_builder::Builder;
+
+    #[derive(Builder)]
+    #[builder(name = "CommandConfig", vis = "pub(crate)", setter(prefix = "with"))]
+    pub struct Command {
+        #[builder(setter(name = "program"))]
+        pub executable: String,
+        #[builder(each = "arg")]
+        pub args: Vec<String>,
+        #[builder(setter(prefix = "set"))]
+        pub current_dir: Option<String>,
+        #[builder(setter(skip))]
+        pub id: u64,
+        #[builder(setter(skip), default = "\"cargo\".to_owned()")]
+        pub origin: String,
+        #[builder(vis = "")]
+        pub secret: Option<String>,
+    }
+
+    pub(crate) fn with_secret(builder: &mut CommandConfig) -> &mut CommandConfig {
+        builder.with_secret("hunter2".to_owned())
+    }
+}
+
+use command::{Command, CommandConfig, CommandConfigError};
+
+fn main() {
+    let mut builder: CommandConfig = Command::builder();
+    builder
+        .program("cargo".to_owned())
+        .arg("build".to_owned())
+        .set_current_dir("..".to_owned());
+    let command = command::with_secret(&mut builder).build().unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.current_dir, Some("..".to_owned()));
+    assert_eq!(command.id, 0);
+    assert_eq!(command.origin, "cargo");
+    assert_eq!(command.secret, Some("hunter2".to_owned()));
+
+    let command = Command::builder()
+        .program("cargo".to_owned())
+        .with_args(vec!["test".to_owned()])
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args, vec!["test"]);
+
+    let error: CommandConfigError = Command::builder().build().err().unwrap();
+
+    assert_eq!(error, CommandConfigError::MissingExecutable);
+}
@@ -0,0 +1,81 @@
+// The derive must not inject `use` items into the caller's module: several
+// builders have to be able to live side by side in one module, and that
+// module may well define its own `Error` type or macros named after standard
+// ones. Everything in the expansion is referred to through absolute paths.
+
+use derive_builder::Builder;
+
+#[derive(Debug)]
+pub enum Error {
+    Io,
+}
+
+#[allow(unused_macros)]
+macro_rules! vec {
+    () => {
+        compile_error!("local vec! macro invoked")
+    };
+}
+
+#[allow(unused_macros)]
+macro_rules! write {
+    ($($tokens:tt)*) => {
+        compile_error!("local write! macro invoked")
+    };
+}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(validate = "validate_jobs", default = "1")]
+    jobs: u32,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct Server {
+    host: String,
+    port: Option<u16>,
+}
+
+fn validate_jobs(jobs: &u32) -> Result<(), &'static str> {
+    if *jobs == 0 {
+        Err("no jobs")
+    } else {
+        Ok(())
+    }
+}
+
+fn run(command: &Command) -> Result<usize, Error> {
+    if command.executable.is_empty() {
+        Err(Error::Io)
+    } else {
+        Ok(command.args.len() + command.jobs as usize)
+    }
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(run(&command).unwrap(), 2);
+
+    let error = Command::builder()
+        .executable("cargo".to_owned())
+        .jobs(0)
+        .build()
+        .err()
+        .unwrap();
+
+    assert_eq!(error.to_string(), "invalid jobs: no jobs");
+
+    let server = Server::builder().host("localhost".to_owned()).build().unwrap();
+
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, None);
+}
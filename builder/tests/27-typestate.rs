@@ -0,0 +1,69 @@
+// With #[builder(typestate)] the builder tracks in its type which required
+// fields have been set: it carries one type parameter per required field,
+// `()` while the field is unset and the field's type once it is. build() is
+// only available after every required field has been given a value, so a
+// missing field is caught by the compiler instead of by build().
+//
+// Optional fields and `each` setters keep working as before, in any order.
+// Setters take and return the builder by value.
+//
+// Generic parameters used only by required fields still work, even though the
+// builder stores those fields as their state parameters.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command<'a> {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: Vec<String>,
+    current_dir: Option<&'a str>,
+    #[builder(default = "1")]
+    jobs: u32,
+    timeout: u64,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Request<'a, T, const N: usize> {
+    body: T,
+    headers: &'a [T; N],
+    retries: Option<u8>,
+}
+
+fn with_release(builder: CommandBuilder<String>) -> CommandBuilder<String> {
+    builder.arg("--release".to_owned())
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .timeout(10)
+        .executable("cargo".to_owned())
+        .current_dir("..")
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert!(command.env.is_empty());
+    assert_eq!(command.current_dir, Some(".."));
+    assert_eq!(command.jobs, 1);
+    assert_eq!(command.timeout, 10);
+
+    let builder: CommandBuilder = Command::builder();
+    let builder: CommandBuilder<String> = builder.executable("cargo".to_owned());
+    let command = with_release(builder).timeout(5).build().unwrap();
+
+    assert_eq!(command.args, vec!["--release"]);
+
+    let headers = [1, 2];
+    let request = Request::builder().headers(&headers).body(3).build().unwrap();
+
+    assert_eq!(request.body, 3);
+    assert_eq!(request.headers, &[1, 2]);
+    assert_eq!(request.retries, None);
+}
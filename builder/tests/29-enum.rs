@@ -0,0 +1,96 @@
+// Deriving Builder on an enum generates a separate builder for every variant
+// that has fields, reachable through `{Enum}::{variant}_builder()`. The
+// builder of variant `Circle` of enum `Shape` is named `ShapeCircleBuilder`
+// and its error `ShapeCircleBuilderError`. Each one behaves like the builder
+// of a struct with the variant's fields; unit variants get no builder.
+//
+// Acronyms in variant names stay one word, so `HTTPPost` becomes
+// `http_post_builder()`.
+
+mod shape {
+    use derive_builder::Builder;
+
+    #[derive(Builder, Debug, PartialEq)]
+    pub enum Shape {
+        Circle {
+            radius: f64,
+        },
+        BigRectangle {
+            width: f64,
+            height: f64,
+            #[builder(each = "tag")]
+            tags: Vec<String>,
+            label: Option<String>,
+        },
+        Point(#[builder(name = "x")] f64, #[builder(name = "y")] f64),
+        Empty,
+    }
+}
+
+mod message {
+    use derive_builder::Builder;
+
+    #[derive(Builder, Debug, PartialEq)]
+    #[builder(into)]
+    pub enum Message<'a, T> {
+        Text { body: T, sender: &'a str },
+        Ack { id: u64 },
+        HTTPPost { url: &'a str },
+    }
+}
+
+use message::Message;
+use shape::{Shape, ShapeCircleBuilderError};
+
+fn main() {
+    let circle = Shape::circle_builder().radius(1.5).build().unwrap();
+
+    assert_eq!(circle, Shape::Circle { radius: 1.5 });
+
+    let rectangle = Shape::big_rectangle_builder()
+        .width(2.0)
+        .height(3.0)
+        .tag("wide".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        rectangle,
+        Shape::BigRectangle {
+            width: 2.0,
+            height: 3.0,
+            tags: vec!["wide".to_owned()],
+            label: None,
+        },
+    );
+
+    let point = Shape::point_builder().x(1.0).y(2.0).build().unwrap();
+
+    assert_eq!(point, Shape::Point(1.0, 2.0));
+
+    let error = Shape::circle_builder().build().unwrap_err();
+
+    assert_eq!(error, ShapeCircleBuilderError::MissingRadius);
+
+    let text: Message<String> = Message::text_builder()
+        .body("hello")
+        .sender("me")
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        text,
+        Message::Text {
+            body: "hello".to_owned(),
+            sender: "me",
+        },
+    );
+
+    let ack: Message<String> = Message::ack_builder().id(7u8).build().unwrap();
+
+    assert_eq!(ack, Message::Ack { id: 7 });
+
+    let post: Message<String> = Message::http_post_builder().url("/").build().unwrap();
+
+    assert_eq!(post, Message::HTTPPost { url: "/" });
+}
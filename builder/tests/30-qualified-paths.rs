@@ -0,0 +1,64 @@
+// Option and Vec fields are recognized by the last segment of their path, so
+// writing them out in full through `std::`, `core::` or `alloc::` behaves the
+// same as the bare prelude names.
+//
+// The macro only sees tokens and cannot look through type aliases. For those,
+// #[builder(optional)] lets a field be left unset, in which case it takes its
+// Default value, and #[builder(required)] makes a field that looks like an
+// Option mandatory, with a setter taking the Option itself.
+
+extern crate alloc;
+
+use derive_builder::Builder;
+
+type MaybeDir = Option<String>;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: std::string::String,
+    #[builder(each = "arg")]
+    args: ::std::vec::Vec<String>,
+    env: alloc::vec::Vec<String>,
+    current_dir: std::option::Option<String>,
+    jobs: core::option::Option<u32>,
+    #[builder(optional)]
+    log_dir: MaybeDir,
+    #[builder(required)]
+    timeout: Option<u64>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .jobs(4)
+        .timeout(None)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert!(command.env.is_empty());
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.jobs, Some(4));
+    assert_eq!(command.log_dir, None);
+    assert_eq!(command.timeout, None);
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .log_dir(Some("target".to_owned()))
+        .timeout(Some(30))
+        .build()
+        .unwrap();
+
+    assert_eq!(command.log_dir, Some("target".to_owned()));
+    assert_eq!(command.timeout, Some(30));
+
+    let error = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .err()
+        .unwrap();
+
+    assert_eq!(error, CommandBuilderError::MissingTimeout);
+}
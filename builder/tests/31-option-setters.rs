@@ -0,0 +1,87 @@
+// Optional fields get a second setter, `<field>_opt`, taking the whole
+// `Option<T>` so that a value which is already optional can be forwarded
+// without unwrapping it first. An explicit `None` passed this way is kept,
+// even if the field has a default.
+//
+// With #[builder(setter(strip_option = false))] the regular setter takes the
+// `Option<T>` itself and no `_opt` variant is generated.
+//
+// Every field also gets a `clear_<field>()` method which resets it to the
+// unset state, as if its setter had never been called.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default = "Some(\"info\".to_owned())")]
+    log_level: Option<String>,
+    #[builder(setter(strip_option = false))]
+    timeout: Option<u64>,
+    #[builder(each = "flag")]
+    flags: Option<Vec<String>>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Request {
+    url: String,
+    retries: Option<u32>,
+}
+
+fn main() {
+    let dir: Option<String> = Some("/tmp".to_owned());
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir_opt(dir)
+        .log_level_opt(None)
+        .timeout(Some(30))
+        .flag("--release".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.current_dir, Some("/tmp".to_owned()));
+    assert_eq!(command.log_level, None);
+    assert_eq!(command.timeout, Some(30));
+    assert_eq!(command.flags, Some(vec!["--release".to_owned()]));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .clear_args()
+        .current_dir("/tmp".to_owned())
+        .clear_current_dir()
+        .log_level_opt(None)
+        .clear_log_level()
+        .timeout(None)
+        .build()
+        .unwrap();
+
+    assert!(command.args.is_empty());
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.log_level, Some("info".to_owned()));
+    assert_eq!(command.timeout, None);
+    assert_eq!(command.flags, None);
+
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned());
+    builder.clear_executable();
+    assert!(builder.build().is_err());
+
+    let request = Request::builder()
+        .url("https://example.com".to_owned())
+        .retries(3)
+        .clear_url()
+        .url("https://example.org".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(request.url, "https://example.org");
+    assert_eq!(request.retries, Some(3));
+}
@@ -0,0 +1,66 @@
+// A setter can run its input through a closure before storing it:
+//
+//     #[builder(setter(transform = |port: &str| port.parse::<u16>()))]
+//
+// The generated setter takes the closure's parameters instead of the field
+// type. The closure returns a `Result`; on success the value is stored, and on
+// failure the error is kept until `build()` reports it through the field's
+// `Invalid{Field}` variant.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(setter(transform = |host: &str| -> Result<String, String> { Ok(host.trim().to_lowercase()) }))]
+    host: String,
+    #[builder(setter(transform = |port: &str| port.parse::<u16>()))]
+    port: u16,
+    #[builder(setter(transform = |scheme: &str, secure: bool| {
+        if scheme.is_empty() {
+            Err("scheme must not be empty")
+        } else {
+            Ok(format!("{}{}", scheme, if secure { "s" } else { "" }))
+        }
+    }))]
+    scheme: Option<String>,
+}
+
+fn main() {
+    let server = Server::builder()
+        .host("  Example.COM ")
+        .port("8080")
+        .scheme("http", true)
+        .build()
+        .unwrap();
+
+    assert_eq!(server.host, "example.com");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.scheme, Some("https".to_owned()));
+
+    let error = Server::builder().host("example.com").port("http").build().err().unwrap();
+    assert_eq!(error, ServerBuilderError::InvalidPort("invalid digit found in string".to_owned()));
+    assert_eq!(error.to_string(), "invalid port: invalid digit found in string");
+
+    let error = Server::builder()
+        .host("example.com")
+        .port("80")
+        .scheme("", false)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(error, ServerBuilderError::InvalidScheme("scheme must not be empty".to_owned()));
+
+    let server = Server::builder()
+        .host("example.com")
+        .port("http")
+        .port("443")
+        .scheme_opt(None)
+        .build()
+        .unwrap();
+
+    assert_eq!(server.port, 443);
+    assert_eq!(server.scheme, None);
+
+    let error = Server::builder().port("443").build().err().unwrap();
+    assert_eq!(error, ServerBuilderError::MissingHost);
+}
@@ -0,0 +1,64 @@
+// An existing value can be turned back into a builder by value through
+// `From<Foo> for FooBuilder`, which every struct builder implements. A `Clone`
+// struct can also opt into `Foo::to_builder()` with #[builder(to_builder)] to
+// do the same from a reference. Every field of the returned builder is
+// already set, so it can be built straight away or adjusted first. Fields with
+// `each` keep their contents and further calls append to them.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+#[builder(to_builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(setter(skip))]
+    id: u64,
+}
+
+#[derive(Builder)]
+pub struct Wrapper(String, #[builder(setter(transform = |value: &str| value.parse::<u8>()))] u8);
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Request {
+    url: String,
+    retries: Option<u32>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .env(vec![])
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+
+    let copy = command
+        .to_builder()
+        .executable("rustc".to_owned())
+        .arg("--release".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(copy.executable, "rustc");
+    assert_eq!(copy.args, vec!["build", "--release"]);
+    assert_eq!(copy.current_dir, Some("..".to_owned()));
+    assert_eq!(copy.id, 0);
+
+    let same = CommandBuilder::from(command.clone()).build().unwrap();
+    assert_eq!(same, command);
+
+    let wrapper = WrapperBuilder::from(Wrapper("a".to_owned(), 1)).build().unwrap();
+    assert_eq!(wrapper.0, "a");
+    assert_eq!(wrapper.1, 1);
+
+    let request = Request::builder().url("https://example.com".to_owned()).build().unwrap();
+    let request = RequestBuilder::from(request).retries(2).build().unwrap();
+    assert_eq!(request.url, "https://example.com");
+    assert_eq!(request.retries, Some(2));
+}
@@ -0,0 +1,48 @@
+// With the `serde` feature of derive_builder enabled, #[builder(serde)] makes
+// the builder implement `serde::Serialize` and `serde::Deserialize`, which
+// requires the `serde` crate in the user's dependencies. Every field is
+// optional, so a partial config can be loaded into a builder, and fields that
+// were never set are left out when serializing. Fields with a setter
+// transform are not serialized.
+//
+// Every builder except typestate ones also gets `merge`, which combines two
+// builders: each field set on the other builder overrides the same field on
+// this one. This is how several partial sources, such as defaults, a config
+// file and command line flags, are layered before calling `build()`.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(serde)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let mut defaults = Command::builder();
+    defaults.executable("cargo".to_owned()).arg("build".to_owned());
+
+    let file: CommandBuilder = serde_json::from_str(r#"{ "env": ["RUST_LOG=info"], "current_dir": ".." }"#).unwrap();
+    let mut flags = Command::builder();
+    flags.executable("rustc".to_owned());
+
+    let command = defaults.merge(file).merge(flags).build().unwrap();
+
+    assert_eq!(command.executable, "rustc");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.env, vec!["RUST_LOG=info"]);
+    assert_eq!(command.current_dir, Some("..".to_owned()));
+
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned()).current_dir("..".to_owned());
+    let json = serde_json::to_string(&builder).unwrap();
+    assert_eq!(json, r#"{"executable":"cargo","current_dir":".."}"#);
+
+    let builder: CommandBuilder = serde_json::from_str(&json).unwrap();
+    assert_eq!(builder.executable, Some("cargo".to_owned()));
+    assert_eq!(builder.args, None);
+}
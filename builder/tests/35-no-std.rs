@@ -0,0 +1,77 @@
+// #[builder(no_std)] makes the expansion usable from crates without the
+// standard library: everything is referred to through `::core` and `::alloc`
+// paths, and the error type implements `core::error::Error` instead of
+// `std::error::Error`. The crate has to declare `extern crate alloc`.
+//
+// This file is itself `no_std`. The standard library is still linked so that
+// the test can run as an ordinary binary, but it is imported under `_`, so
+// any `::std` path in the generated code would fail to resolve.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std as _;
+
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use derive_builder::Builder;
+
+fn check_port(port: &u16) -> Result<(), &'static str> {
+    if *port == 0 {
+        Err("port must not be zero")
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Builder)]
+#[builder(no_std)]
+pub struct Device {
+    name: String,
+    #[builder(validate = "check_port")]
+    port: u16,
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+    label: Option<String>,
+    #[builder(setter(transform = |level: &str| level.parse::<u8>()))]
+    level: u8,
+}
+
+#[derive(Builder)]
+#[builder(no_std)]
+pub enum Message<T> {
+    Ping,
+    Data { payload: T, checksum: Option<u32> },
+}
+
+fn main() {
+    let device = Device::builder()
+        .name("sensor".to_owned())
+        .port(8080)
+        .tag("outdoor".to_owned())
+        .level("3")
+        .build()
+        .unwrap();
+
+    assert_eq!(device.name, "sensor");
+    assert_eq!(device.port, 8080);
+    assert_eq!(device.tags, ["outdoor"]);
+    assert_eq!(device.label, None);
+    assert_eq!(device.level, 3);
+
+    let error = Device::builder().name("sensor".to_owned()).port(0).level("1").build().err().unwrap();
+    assert_eq!(error, DeviceBuilderError::InvalidPort("port must not be zero".to_owned()));
+    assert_eq!(error.to_string(), "invalid port: port must not be zero");
+
+    let error: &dyn core::error::Error = &DeviceBuilderError::MissingName;
+    assert_eq!(error.to_string(), "name is required");
+
+    match Message::data_builder().payload(1u8).build().unwrap() {
+        Message::Data { payload, checksum } => {
+            assert_eq!(payload, 1);
+            assert_eq!(checksum, None);
+        }
+        Message::Ping => unreachable!(),
+    }
+}
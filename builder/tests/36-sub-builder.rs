@@ -0,0 +1,80 @@
+// A field whose type also derives Builder can be marked with
+// #[builder(sub_builder)]. The outer builder then stores the nested builder
+// instead of a value, and the field's setter becomes an accessor returning
+// `&mut` to it so the nested value is edited in place. The nested type must
+// use the default mutable builder pattern for that to work.
+//
+// `build()` builds every nested builder, starting from an empty one if it was
+// never touched. A nested error is reported through the `Nested{Field}`
+// variant, with the field name prepended to the nested message so that the
+// path to the failing field can be read off deep config trees.
+//
+// `merge` merges nested builders field by field rather than replacing the
+// whole nested builder when both sides have one.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+pub struct ServerConfig {
+    host: String,
+    port: u16,
+}
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+pub struct LogConfig {
+    #[builder(default = "\"info\".to_owned()")]
+    level: String,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Config {
+    name: String,
+    #[builder(sub_builder)]
+    server: ServerConfig,
+    #[builder(sub_builder)]
+    log: LogConfig,
+}
+
+#[derive(Builder, Debug)]
+pub struct Deployment {
+    #[builder(sub_builder)]
+    config: Config,
+}
+
+fn main() {
+    let mut builder = Config::builder();
+    builder.name("app".to_owned());
+    builder.server().host("localhost".to_owned()).port(8080);
+    builder.server().port(9090);
+    let config = builder.build().unwrap();
+
+    assert_eq!(config.name, "app");
+    assert_eq!(config.server, ServerConfig { host: "localhost".to_owned(), port: 9090 });
+    assert_eq!(config.log.level, "info");
+
+    let mut builder = Config::builder();
+    builder.name("app".to_owned());
+    builder.server().host("localhost".to_owned());
+    let error = builder.build().err().unwrap();
+    assert_eq!(error, ConfigBuilderError::NestedServer("port is required".to_owned()));
+    assert_eq!(error.to_string(), "server: port is required");
+
+    let mut builder = Deployment::builder();
+    builder.config().name("app".to_owned()).log().level("debug".to_owned());
+    let error = builder.build().err().unwrap();
+    assert_eq!(error.to_string(), "config: server: host is required");
+
+    let mut builder = ConfigBuilder::from(config);
+    builder.server().port(443);
+    let config = builder.build().unwrap();
+    assert_eq!(config.server.host, "localhost");
+    assert_eq!(config.server.port, 443);
+
+    let mut defaults = Config::builder();
+    defaults.name("app".to_owned());
+    defaults.server().host("localhost".to_owned()).port(80);
+    let mut overrides = Config::builder();
+    overrides.server().port(8080);
+    let config = defaults.merge(overrides).build().unwrap();
+    assert_eq!(config.server, ServerConfig { host: "localhost".to_owned(), port: 8080 });
+}
@@ -0,0 +1,94 @@
+// Every generated item is documented, so the derive can be used from crates
+// with #![deny(missing_docs)]. A field's own doc comment is forwarded to its
+// setter; otherwise the setter gets a short default such as "Sets `field`".
+//
+// #[builder(doc = "...")] replaces the generated docs: on the struct for the
+// builder type, and on a field for that field's setter.
+
+//! Documented builders.
+
+#![deny(missing_docs)]
+
+use derive_builder::Builder;
+
+fn check_level(level: &u8) -> Result<(), String> {
+    if *level > 5 {
+        Err("level too high".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+/// A command to run.
+#[derive(Builder)]
+#[builder(doc = "Assembles a [`Command`] one field at a time.")]
+pub struct Command {
+    /// The program to run.
+    pub executable: String,
+    /// Arguments passed to the program.
+    #[builder(each = "arg")]
+    pub args: Vec<String>,
+    #[builder(doc = "Sets the directory the program runs in.")]
+    current_dir: Option<String>,
+    #[builder(validate = "check_level")]
+    level: u8,
+    #[builder(setter(transform = |port: &str| port.parse::<u16>()))]
+    port: u16,
+    #[builder(sub_builder)]
+    log: Log,
+}
+
+/// Logging settings.
+#[derive(Builder)]
+#[builder(validate = "Log::check")]
+pub struct Log {
+    /// Whether to log at all.
+    #[builder(default)]
+    pub enabled: bool,
+}
+
+impl Log {
+    fn check(_builder: &LogBuilder) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// An HTTP request.
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Request {
+    /// Where to send the request.
+    pub url: String,
+}
+
+/// A message with a payload.
+#[derive(Builder)]
+pub enum Message<T> {
+    /// No payload.
+    Ping,
+    /// Some data.
+    Data {
+        /// The data itself.
+        payload: T,
+    },
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .level(1)
+        .port("80");
+    builder.log().enabled(true);
+    let command = builder.build().unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert!(command.log.enabled);
+
+    let request = Request::builder().url("https://example.com".to_owned()).build().unwrap();
+    assert_eq!(request.url, "https://example.com");
+
+    let message = Message::data_builder().payload(1).build().unwrap();
+    assert!(matches!(message, Message::Data { payload: 1 }));
+}
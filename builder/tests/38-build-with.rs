@@ -0,0 +1,65 @@
+// Some defaults can only be computed at runtime from outside state, such as
+// the environment or a handle to a resource. #[builder(context = "...")] on
+// the struct names a context type and adds a `build_with(ctx: &Context)`
+// method next to `build()`. A field marked with
+// #[builder(default_with = "function")] is then filled in, when unset, by
+// calling `function(ctx)`.
+//
+// `build()` has no context to call these functions with, so there such a
+// field falls back to the usual rules and is required if it has no other
+// default.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+pub struct Env {
+    vars: HashMap<String, String>,
+}
+
+fn home_dir(env: &Env) -> String {
+    env.vars.get("HOME").cloned().unwrap_or_else(|| "/".to_owned())
+}
+
+fn shell(env: &Env) -> Option<String> {
+    env.vars.get("SHELL").cloned()
+}
+
+#[derive(Builder)]
+#[builder(context = "Env")]
+pub struct Session {
+    user: String,
+    #[builder(default_with = "home_dir")]
+    home: String,
+    #[builder(default_with = "shell")]
+    shell: Option<String>,
+}
+
+fn main() {
+    let env = Env {
+        vars: vec![("HOME".to_owned(), "/home/ferris".to_owned())].into_iter().collect(),
+    };
+
+    let session = Session::builder().user("ferris".to_owned()).build_with(&env).unwrap();
+    assert_eq!(session.user, "ferris");
+    assert_eq!(session.home, "/home/ferris");
+    assert_eq!(session.shell, None);
+
+    let session = Session::builder()
+        .user("ferris".to_owned())
+        .home("/tmp".to_owned())
+        .shell("zsh".to_owned())
+        .build_with(&env)
+        .unwrap();
+    assert_eq!(session.home, "/tmp");
+    assert_eq!(session.shell, Some("zsh".to_owned()));
+
+    let error = Session::builder().user("ferris".to_owned()).build().err().unwrap();
+    assert_eq!(error, SessionBuilderError::MissingHome);
+
+    let session = Session::builder()
+        .user("ferris".to_owned())
+        .home("/root".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(session.shell, None);
+}
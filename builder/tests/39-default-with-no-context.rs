@@ -0,0 +1,16 @@
+// `default_with` functions are called with the context passed to
+// `build_with`, so the struct has to declare the context type.
+
+use derive_builder::Builder;
+
+fn home_dir(_env: &()) -> String {
+    "/".to_owned()
+}
+
+#[derive(Builder)]
+pub struct Session {
+    #[builder(default_with = "home_dir")]
+    home: String,
+}
+
+fn main() {}
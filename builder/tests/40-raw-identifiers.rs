@@ -0,0 +1,55 @@
+// Fields may be named with raw identifiers. The `r#` prefix is not part of
+// the name as far as the generated error variants and messages go, so a field
+// `r#type` is reported through `MissingType` as "type is required".
+
+use derive_builder::Builder;
+
+fn check_match(value: &u8) -> Result<(), String> {
+    if *value == 0 {
+        Err("must not be zero".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Builder, Debug)]
+pub struct Token {
+    r#type: String,
+    #[builder(validate = "check_match")]
+    r#match: u8,
+    r#loop: Option<bool>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Request {
+    r#type: String,
+}
+
+fn main() {
+    let token = Token::builder()
+        .r#type("ident".to_owned())
+        .r#match(1)
+        .r#loop(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(token.r#type, "ident");
+    assert_eq!(token.r#match, 1);
+    assert_eq!(token.r#loop, Some(true));
+
+    let error = Token::builder().r#match(1).build().unwrap_err();
+    assert_eq!(error, TokenBuilderError::MissingType);
+    assert_eq!(error.to_string(), "type is required");
+
+    let error = Token::builder().r#type("ident".to_owned()).r#match(0).build().unwrap_err();
+    assert_eq!(error, TokenBuilderError::InvalidMatch("must not be zero".to_owned()));
+    assert_eq!(error.to_string(), "invalid match: must not be zero");
+
+    let mut builder = Token::builder();
+    builder.r#type("ident".to_owned()).clear_type();
+    assert_eq!(builder.r#match(1).build().unwrap_err(), TokenBuilderError::MissingType);
+
+    let request = Request::builder().r#type("get".to_owned()).build().unwrap();
+    assert_eq!(request.r#type, "get");
+}
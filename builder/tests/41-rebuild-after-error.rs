@@ -0,0 +1,84 @@
+// With the default `&mut self` builder, a failed build() must leave the builder
+// as it was so that the caller can fix the offending field and try again. The
+// checks and validators therefore run against the stored values by reference,
+// and nothing is moved out of the builder until every step has succeeded.
+//
+// Nested builders are checked the same way before any of them is built, so a
+// failing nested builder leaves the others as they were too, including which
+// of their fields were never set.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    #[builder(validate = "validate_jobs", default = "1")]
+    jobs: u32,
+    #[builder(setter(transform = |port: &str| port.parse::<u16>().map_err(|error| error.to_string())))]
+    port: u16,
+}
+
+fn validate_jobs(jobs: &u32) -> Result<(), &'static str> {
+    if *jobs == 0 {
+        Err("at least one job is needed")
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct ServerConfig {
+    host: String,
+    #[builder(default = "80")]
+    port: u16,
+}
+
+#[derive(Builder, Debug)]
+pub struct Config {
+    #[builder(sub_builder)]
+    primary: ServerConfig,
+    #[builder(sub_builder)]
+    fallback: ServerConfig,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned()).jobs(0).port("8080");
+    assert_eq!(
+        builder.build().unwrap_err(),
+        CommandBuilderError::InvalidJobs("at least one job is needed".to_owned()),
+    );
+
+    builder.jobs(2).port("http");
+    assert_eq!(
+        builder.build().unwrap_err(),
+        CommandBuilderError::InvalidPort("invalid digit found in string".to_owned()),
+    );
+
+    builder.port("8080");
+    let command = builder.build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.jobs, 2);
+    assert_eq!(command.port, 8080);
+
+    // A successful build still moves the values out.
+    assert_eq!(builder.build().unwrap_err(), CommandBuilderError::MissingExecutable);
+
+    let mut builder = Config::builder();
+    builder.primary().host("localhost".to_owned());
+    builder.fallback().port(8080);
+    assert_eq!(
+        builder.build().unwrap_err(),
+        ConfigBuilderError::NestedFallback("host is required".to_owned()),
+    );
+
+    // `primary.port` is still unset, so merging doesn't override it with the
+    // default.
+    let mut other = Config::builder();
+    other.primary().port(9);
+    other.merge(builder);
+    other.fallback().host("localhost".to_owned());
+    let config = other.build().unwrap();
+    assert_eq!(config.primary, ServerConfig { host: "localhost".to_owned(), port: 9 });
+    assert_eq!(config.fallback, ServerConfig { host: "localhost".to_owned(), port: 8080 });
+}
@@ -0,0 +1,21 @@
+// A `sub_builder` field is edited in place through a `&mut` accessor, and the
+// outer builder builds and merges it through `&mut` as well, so the nested
+// type has to keep the default mutable builder pattern. The derive only sees
+// the field's type, so it checks this through the nested builder's method
+// signatures: an owned nested builder is reported at the field's type.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "owned")]
+pub struct ServerConfig {
+    host: String,
+}
+
+#[derive(Builder)]
+pub struct Config {
+    #[builder(sub_builder)]
+    server: ServerConfig,
+}
+
+fn main() {}
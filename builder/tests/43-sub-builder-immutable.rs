@@ -0,0 +1,19 @@
+// An immutable nested builder is rejected the same way. Its setters and
+// `merge` return a new builder instead of editing the one behind the accessor,
+// so both the caller's edits and the outer `merge` would be silently lost.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct LogConfig {
+    level: String,
+}
+
+#[derive(Builder)]
+pub struct Config {
+    #[builder(sub_builder)]
+    log: LogConfig,
+}
+
+fn main() {}
@@ -0,0 +1,53 @@
+// Some field attributes don't make sense together with #[builder(sub_builder)]
+// and are rejected at the field's type.
+//
+// A field validator would need the built nested value, but a failing build()
+// must not have built any nested builder yet. Validate the nested struct
+// itself instead.
+//
+// An `Option` field has no builder of its own to nest, and an immutable outer
+// builder can't hand out `&mut` access to the nested builders it holds.
+// A fallible `try_default_with` provider is not supported on nested builders.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct ServerConfig {
+    host: String,
+}
+
+fn check_server(_server: &ServerConfig) -> Result<(), String> {
+    Ok(())
+}
+
+#[derive(Builder)]
+pub struct Config {
+    #[builder(sub_builder, validate = "check_server")]
+    server: ServerConfig,
+}
+
+#[derive(Builder)]
+pub struct MaybeConfig {
+    #[builder(sub_builder)]
+    server: Option<ServerConfig>,
+}
+
+#[derive(Builder)]
+#[builder(pattern = "immutable")]
+pub struct FrozenConfig {
+    #[builder(sub_builder)]
+    server: ServerConfig,
+}
+
+fn default_server(_env: &()) -> Result<ServerConfig, String> {
+    Err("no server".to_owned())
+}
+
+#[derive(Builder)]
+#[builder(context = "()")]
+pub struct ContextConfig {
+    #[builder(sub_builder, try_default_with = "default_server")]
+    server: ServerConfig,
+}
+
+fn main() {}
@@ -0,0 +1,46 @@
+// A default that is looked up at runtime may not be available. With
+// #[builder(try_default_with = "function")] the function returns a `Result`,
+// and an error from it is reported by `build_with` through the field's
+// `Default{Field}` variant, with the error's message. Like every other
+// failure, it leaves the builder as it was.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+pub struct Env {
+    vars: HashMap<String, String>,
+}
+
+fn home_dir(env: &Env) -> Result<String, String> {
+    env.vars.get("HOME").cloned().ok_or_else(|| "HOME is not set".to_owned())
+}
+
+#[derive(Builder, Debug)]
+#[builder(context = "Env")]
+pub struct Session {
+    user: String,
+    #[builder(try_default_with = "home_dir")]
+    home: String,
+}
+
+fn main() {
+    let mut env = Env { vars: HashMap::new() };
+
+    let mut builder = Session::builder();
+    builder.user("ferris".to_owned());
+    let error = builder.build_with(&env).unwrap_err();
+    assert_eq!(error, SessionBuilderError::DefaultHome("HOME is not set".to_owned()));
+    assert_eq!(error.to_string(), "could not compute default for home: HOME is not set");
+
+    env.vars.insert("HOME".to_owned(), "/home/ferris".to_owned());
+    let session = builder.build_with(&env).unwrap();
+    assert_eq!(session.user, "ferris");
+    assert_eq!(session.home, "/home/ferris");
+
+    let session = Session::builder()
+        .user("ferris".to_owned())
+        .home("/tmp".to_owned())
+        .build_with(&Env { vars: HashMap::new() })
+        .unwrap();
+    assert_eq!(session.home, "/tmp");
+}
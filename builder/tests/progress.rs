@@ -10,4 +10,40 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-tuple-struct.rs");
+    t.pass("tests/11-unit-struct.rs");
+    t.pass("tests/12-build-error.rs");
+    t.pass("tests/13-default-field.rs");
+    t.pass("tests/14-generics.rs");
+    t.pass("tests/15-builder-pattern.rs");
+    t.pass("tests/16-into-setter.rs");
+    t.pass("tests/17-validation.rs");
+    t.pass("tests/18-each-collections.rs");
+    t.compile_fail("tests/19-missing-equals.rs");
+    t.compile_fail("tests/20-non-string-value.rs");
+    t.compile_fail("tests/21-each-non-collection.rs");
+    t.compile_fail("tests/22-duplicate-attribute.rs");
+    t.compile_fail("tests/23-not-a-struct.rs");
+    t.compile_fail("tests/24-unknown-pattern.rs");
+    t.pass("tests/25-setter-naming.rs");
+    t.pass("tests/26-no-use-items.rs");
+    t.pass("tests/27-typestate.rs");
+    t.compile_fail("tests/28-typestate-missing-field.rs");
+    t.pass("tests/29-enum.rs");
+    t.pass("tests/30-qualified-paths.rs");
+    t.pass("tests/31-option-setters.rs");
+    t.pass("tests/32-setter-transform.rs");
+    t.pass("tests/33-to-builder.rs");
+    t.pass("tests/34-serde.rs");
+    t.pass("tests/35-no-std.rs");
+    t.pass("tests/36-sub-builder.rs");
+    t.pass("tests/37-docs.rs");
+    t.pass("tests/38-build-with.rs");
+    t.compile_fail("tests/39-default-with-no-context.rs");
+    t.pass("tests/40-raw-identifiers.rs");
+    t.pass("tests/41-rebuild-after-error.rs");
+    t.compile_fail("tests/42-sub-builder-pattern.rs");
+    t.compile_fail("tests/43-sub-builder-immutable.rs");
+    t.compile_fail("tests/44-sub-builder-unsupported.rs");
+    t.pass("tests/45-try-default-with.rs");
 }
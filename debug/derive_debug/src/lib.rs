@@ -1,122 +1,210 @@
-#![feature(entry_insert)]
-
 use quote::quote;
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput, Ident, Data, Fields, Field};
 use std::collections::HashMap;
+use syn::ext::IdentExt;
 use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
 
 type DebugFields = Punctuated<Field, syn::token::Comma>;
+type DebugBounds = Punctuated<syn::WherePredicate, syn::token::Comma>;
 
-fn get_fields_attribute_values(fields: &DebugFields) -> HashMap<String, String> {
-    let mut hasher = HashMap::new();
-
-    fields.iter().for_each(|field| {
-        field.attrs.iter()
-            .for_each(|e| {
-                if let syn::Meta::NameValue(name_value) = e.parse_meta().unwrap() {
-                    let name = name_value.path.segments[0].ident.to_string();
+#[derive(Default)]
+struct DebugAttributes {
+    format: Option<syn::LitStr>,
+    bound: Option<DebugBounds>,
+}
 
-                    if name != "debug" {
-                        todo!();
+fn get_debug_attributes(attrs: &[syn::Attribute]) -> syn::Result<DebugAttributes> {
+    let mut attributes = DebugAttributes::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("debug")) {
+        match attr.parse_meta()? {
+            syn::Meta::NameValue(syn::MetaNameValue { lit: syn::Lit::Str(lit), .. }) => attributes.format = Some(lit),
+            syn::Meta::List(list) => {
+                for nested in list.nested {
+                    match nested {
+                        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                            path,
+                            lit: syn::Lit::Str(lit),
+                            ..
+                        })) if path.is_ident("bound") => {
+                            attributes.bound = Some(lit.parse_with(DebugBounds::parse_terminated)?);
+                        }
+                        nested => return Err(syn::Error::new_spanned(nested, "expected `bound = \"...\"`")),
                     }
-
-                    let value: String = if let syn::Lit::Str(lit) = name_value.lit {
-                        lit.value()
-                    } else {
-                        todo!()
-                    };
-
-                    hasher.insert(field.ident.as_ref().unwrap().to_string(), value);
-                } else {
-                    todo!();
                 }
-            });
-    });
+            }
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "expected `debug = \"...\"` or `debug(bound = \"...\")`",
+                ))
+            }
+        }
+    }
 
-    hasher
+    Ok(attributes)
 }
 
-fn get_fields_to_show_in_debug(fields: &DebugFields, fields_with_attributes: &HashMap<String, String>) -> (String, Vec<proc_macro2::TokenStream>) {
-    let mut formatter = String::from("{} {{ ");
-    let debug_fields: Vec<_> = fields
+fn get_fields_attribute_values(fields: &DebugFields) -> syn::Result<HashMap<String, DebugAttributes>> {
+    fields
         .iter()
-        .enumerate()
-        .map(|(index, field)| {
-            let ident: &Ident = field.ident.as_ref().unwrap();
-            let stringified_ident = ident.to_string();
+        .map(|field| Ok((field.ident.as_ref().unwrap().to_string(), get_debug_attributes(&field.attrs)?)))
+        .collect()
+}
 
-            if index == 0 {
-                formatter.push_str("{}");
-            } else {
-                formatter.push_str(", {}");
-            }
+fn get_fields_to_show_in_debug(
+    fields: &DebugFields,
+    fields_with_attributes: &HashMap<String, DebugAttributes>,
+) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .map(|field| {
+            let ident: &Ident = field.ident.as_ref().unwrap();
+            // Printed without `r#`, like #[derive(Debug)] does.
+            let field_name = ident.unraw().to_string();
 
-            if fields_with_attributes.contains_key(&stringified_ident) {
-                let formatting = fields_with_attributes.get(&stringified_ident).unwrap();
+            if let Some(formatting) = &fields_with_attributes[&ident.to_string()].format {
                 quote! {
-                    format!("{}: {}", #stringified_ident, format!(#formatting, self.#ident))
+                    .field(#field_name, &::std::format_args!(#formatting, self.#ident))
                 }
             } else {
                 quote! {
-                    format!("{}: \"{}\"", #stringified_ident, &self.#ident)
+                    .field(#field_name, &self.#ident)
                 }
             }
         })
-        .collect();
+        .collect()
+}
+
+struct TypeParamUses<'a> {
+    type_params: Vec<&'a Ident>,
+    bounded_types: Vec<syn::TypePath>,
+}
 
-    formatter.push_str(" }}");
+impl<'ast> Visit<'ast> for TypeParamUses<'_> {
+    fn visit_type_path(&mut self, type_path: &'ast syn::TypePath) {
+        // `<T as Trait>::Value` is bounded as a whole, like `T::Value`.
+        let root = match &type_path.qself {
+            Some(qself) => match &*qself.ty {
+                syn::Type::Path(self_path) if self_path.qself.is_none() => Some(&self_path.path.segments[0].ident),
+                _ => None,
+            },
+            None => Some(&type_path.path.segments[0].ident),
+        };
+
+        if root.is_some_and(|root| self.type_params.contains(&root)) {
+            if !self.bounded_types.contains(type_path) {
+                self.bounded_types.push(type_path.clone());
+            }
 
-    (formatter, debug_fields)
+            return;
+        }
+
+        // `PhantomData<T>` is `Debug` whatever `T` is.
+        if type_path.path.segments.last().unwrap().ident == "PhantomData" {
+            return;
+        }
+
+        visit::visit_type_path(self, type_path);
+    }
+}
+
+// Bounds every type parameter that a field uses directly with `Debug`, and for
+// uses through an associated type like `T::Value`, bounds that path instead.
+// Only the parameters themselves are bounded, never types that contain them,
+// so structs referring to each other don't send trait resolution in circles.
+// A field with #[debug(bound = "...")] contributes its bounds instead.
+fn get_debug_bounds(
+    generics: &syn::Generics,
+    fields: &DebugFields,
+    fields_with_attributes: &HashMap<String, DebugAttributes>,
+) -> Vec<syn::WherePredicate> {
+    let mut bounds = Vec::new();
+    let mut uses = TypeParamUses {
+        type_params: generics.type_params().map(|param| &param.ident).collect(),
+        bounded_types: Vec::new(),
+    };
+
+    for field in fields {
+        match &fields_with_attributes[&field.ident.as_ref().unwrap().to_string()].bound {
+            Some(bound) => bounds.extend(bound.iter().cloned()),
+            None => uses.visit_type(&field.ty),
+        }
+    }
+
+    bounds.extend(
+        uses.bounded_types
+            .into_iter()
+            .map(|bounded_type| -> syn::WherePredicate { syn::parse_quote! { #bounded_type: std::fmt::Debug } }),
+    );
+
+    bounds
 }
 
 #[proc_macro_derive(CustomDebug, attributes(debug))]
 pub fn derive(input: TokenStream) -> TokenStream {
     let parsed_ast = parse_macro_input!(input as DeriveInput);
+
+    expand_debug(&parsed_ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_debug(parsed_ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let struct_structure = &parsed_ast.ident;
-    let struct_name = struct_structure.to_string();
-    let fields = if let Data::Struct(ref data_struct) = parsed_ast.data {
-        if let Fields::Named(ref fields) = data_struct.fields {
-            &fields.named
-        } else {
-            todo!();
+    let struct_name = struct_structure.unraw().to_string();
+    let fields = match &parsed_ast.data {
+        Data::Struct(data_struct) => match &data_struct.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    data_struct.struct_token,
+                    "CustomDebug can only be derived for structs with named fields",
+                ))
+            }
+        },
+        Data::Enum(data_enum) => {
+            return Err(syn::Error::new_spanned(
+                data_enum.enum_token,
+                "CustomDebug can only be derived for structs with named fields",
+            ))
+        }
+        Data::Union(data_union) => {
+            return Err(syn::Error::new_spanned(
+                data_union.union_token,
+                "CustomDebug can only be derived for structs with named fields",
+            ))
         }
-    } else {
-        todo!();
     };
-    let fields_with_attributes = get_fields_attribute_values(fields);
-    let (formatter, debug_fields) = get_fields_to_show_in_debug(fields, &fields_with_attributes);
-    let mut generic = quote! {};
-    let mut generic_impl = quote! {};
-
-    if parsed_ast.generics.params.len() > 0 {
-        let generic_names: Vec<&syn::Ident> = parsed_ast.generics.params
-            .iter()
-            .map(|param| {
-                if let syn::GenericParam::Type(generic_param) = param {
-                    &generic_param.ident
-                } else {
-                    todo!();
-                }
-            })
-            .collect();
-
-        generic = quote! {
-            <#(#generic_names),*>
-        };
+    let struct_attributes = get_debug_attributes(&parsed_ast.attrs)?;
 
-        generic_impl = quote! {
-            <#(#generic_names: std::fmt::Display),*>
-        };
+    if let Some(format) = struct_attributes.format {
+        return Err(syn::Error::new_spanned(format, "a format string can only be given for a field"));
     }
 
+    let fields_with_attributes = get_fields_attribute_values(fields)?;
+    let debug_fields = get_fields_to_show_in_debug(fields, &fields_with_attributes);
+    let mut generics = parsed_ast.generics.clone();
+    let debug_bounds = match struct_attributes.bound {
+        Some(bound) => bound.into_iter().collect(),
+        None => get_debug_bounds(&generics, fields, &fields_with_attributes),
+    };
+
+    generics.make_where_clause().predicates.extend(debug_bounds);
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
     let returned_token = quote! {
-        impl#generic_impl std::fmt::Debug for #struct_structure#generic {
+        impl #impl_generics std::fmt::Debug for #struct_structure #type_generics #where_clause {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                write!(f, #formatter, #struct_name, #(#debug_fields,)*)
+                f.debug_struct(#struct_name)
+                    #(#debug_fields)*
+                    .finish()
             }
         }
     };
 
-    returned_token.into()
+    Ok(returned_token)
 }
@@ -0,0 +1,52 @@
+// The generated impl goes through `Formatter::debug_struct`, so it formats
+// exactly like #[derive(Debug)] would: every field through its own `Debug`
+// impl, nested structs included, and flags such as `{:#?}` are honoured. A
+// field with a #[debug = "..."] format string is still printed with that
+// format. Raw identifiers are printed without their `r#` prefix.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Inner {
+    count: u32,
+    #[debug = "0x{:x}"]
+    flags: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Outer {
+    name: &'static str,
+    inner: Inner,
+    ratio: f64,
+}
+
+#[derive(CustomDebug)]
+pub struct Token {
+    r#type: &'static str,
+}
+
+fn main() {
+    let outer = Outer {
+        name: "F",
+        inner: Inner { count: 5, flags: 255 },
+        ratio: 0.5,
+    };
+
+    let debug = format!("{:?}", outer);
+    let expected = r#"Outer { name: "F", inner: Inner { count: 5, flags: 0xff }, ratio: 0.5 }"#;
+    assert_eq!(debug, expected);
+
+    let debug = format!("{:#?}", outer);
+    let expected = r#"Outer {
+    name: "F",
+    inner: Inner {
+        count: 5,
+        flags: 0xff,
+    },
+    ratio: 0.5,
+}"#;
+    assert_eq!(debug, expected);
+
+    let debug = format!("{:?}", Token { r#type: "ident" });
+    assert_eq!(debug, r#"Token { type: "ident" }"#);
+}
@@ -0,0 +1,47 @@
+// #[debug(bound = "...")] can also go on a single field. Its bounds replace
+// only the ones that would have been inferred from that field's type; the
+// other fields still get their bounds inferred as usual.
+//
+// Attributes other than `debug`, such as doc comments, are left alone.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T: Trait, U> {
+    /// Bounded by hand, since `Field<T>` only needs `T::Value: Debug`.
+    #[debug(bound = "T::Value: Debug")]
+    field: Field<T>,
+    /// Still inferred to need `U: Debug`.
+    normal: U,
+}
+
+#[derive(CustomDebug)]
+pub struct Field<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<Wrapper<Id, u8>>();
+
+    let wrapper = Wrapper::<Id, _> {
+        field: Field { values: vec![1, 2] },
+        normal: "n",
+    };
+
+    let debug = format!("{:?}", wrapper);
+    let expected = r#"Wrapper { field: Field { values: [1, 2] }, normal: "n" }"#;
+    assert_eq!(debug, expected);
+}
@@ -0,0 +1,35 @@
+// An associated type can also be named with a qualified path, which is needed
+// when more than one trait in scope has an associated type of that name:
+//
+//     <T as Trait>::Value
+//
+// It is bounded the same way as `T::Value`, as a whole, rather than by putting
+// a `Debug` bound on `T` itself.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Field<T: Trait> {
+    value: <T as Trait>::Value,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug, but its associated type does.
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<Field<Id>>();
+
+    let field = Field::<Id> { value: 7 };
+    assert_eq!(format!("{:?}", field), "Field { value: 7 }");
+}
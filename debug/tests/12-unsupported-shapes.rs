@@ -0,0 +1,23 @@
+// Only structs with named fields are supported. Tuple structs, unit structs,
+// enums and unions are rejected with an error at the keyword introducing them.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Point(i32, i32);
+
+#[derive(CustomDebug)]
+pub struct Marker;
+
+#[derive(CustomDebug)]
+pub enum Shape {
+    Circle { radius: f64 },
+}
+
+#[derive(CustomDebug)]
+pub union Number {
+    integer: u64,
+    float: f64,
+}
+
+fn main() {}
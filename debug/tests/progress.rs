@@ -5,8 +5,12 @@ fn tests() {
     t.pass("tests/02-impl-debug.rs");
     t.pass("tests/03-custom-format.rs");
     t.pass("tests/04-type-parameter.rs");
-    //t.pass("tests/05-phantom-data.rs");
-    //t.pass("tests/06-bound-trouble.rs");
-    //t.pass("tests/07-associated-type.rs");
-    //t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/05-phantom-data.rs");
+    t.pass("tests/06-bound-trouble.rs");
+    t.pass("tests/07-associated-type.rs");
+    t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-debug-struct.rs");
+    t.pass("tests/10-field-bound.rs");
+    t.pass("tests/11-qualified-associated-type.rs");
+    t.compile_fail("tests/12-unsupported-shapes.rs");
 }